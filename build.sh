@@ -62,6 +62,7 @@ if [ "$BUILD_ONLY" -eq 0 ]; then
   run=$(buildah from scratch)
   buildah copy "$run" "$mount/etc/ssl/certs/ca-certificates.crt" /etc/ssl/certs/ca-certificates.crt
   buildah copy "$run" "$mount/build/target/release/retrograde" /retrograde
+  buildah copy "$run" "$mount/build/target/release/retrograde-admin" /retrograde-admin
   buildah config --env PORT=8000 --port 8000 --entrypoint '["/retrograde"]' "$run"
 
   buildah unmount "$build"
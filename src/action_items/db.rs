@@ -0,0 +1,293 @@
+use firestore::paths;
+use firestore::FirestoreDb;
+use firestore::FirestoreReference;
+use firestore::FirestoreWritePrecondition;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+
+use super::models::*;
+use crate::boards::db::{generate_document_id, MAX_BATCH_WRITES};
+use crate::error::{check_batch, Error};
+use crate::participants::models::Participant;
+
+fn card_reference(
+  firestore: &FirestoreDb,
+  board_id: &String,
+  card_id: &String,
+) -> Result<FirestoreReference, Error> {
+  Ok(FirestoreReference(format!(
+    "{}/cards/{}",
+    firestore.parent_path("boards", board_id)?,
+    card_id
+  )))
+}
+
+pub async fn new(
+  firestore: &FirestoreDb,
+  participant: &Participant,
+  board_id: &String,
+  action_item: ActionItemMessage,
+) -> Result<ActionItem, Error> {
+  let card = action_item
+    .card
+    .as_ref()
+    .map(|card_id| card_reference(firestore, board_id, card_id))
+    .transpose()?;
+  let mut new_action_item: NewActionItem = action_item.into();
+  new_action_item.card = card;
+  new_action_item.owner = Some(FirestoreReference(format!(
+    "{}/participants/{}",
+    firestore.get_documents_path(),
+    participant.id
+  )));
+  firestore
+    .fluent()
+    .insert()
+    .into("action_items")
+    .generate_document_id()
+    .parent(firestore.parent_path("boards", board_id)?)
+    .object(&new_action_item)
+    .execute::<ActionItemInFirestore>()
+    .await
+    .map(|action_item| action_item.into())
+    .map_err(|e| e.into())
+}
+
+// A carry-over can hold more action items than a single commit allows, so they're
+// written in batches. A failure part way leaves some of them written, so callers
+// remove the board they were meant for.
+pub async fn insert_all(
+  firestore: &FirestoreDb,
+  board_id: &String,
+  action_items: Vec<NewActionItem>,
+) -> Result<(), Error> {
+  let board_path = firestore.parent_path("boards", board_id)?;
+  let writer = firestore.create_simple_batch_writer().await?;
+  for chunk in action_items.chunks(MAX_BATCH_WRITES) {
+    let mut batch = writer.new_batch();
+    for action_item in chunk {
+      firestore
+        .fluent()
+        .update()
+        .in_col("action_items")
+        .precondition(FirestoreWritePrecondition::Exists(false))
+        .document_id(generate_document_id())
+        .parent(&board_path)
+        .object(action_item)
+        .add_to_batch(&mut batch)?;
+    }
+    check_batch(&batch.write().await?)?;
+  }
+  Ok(())
+}
+
+pub async fn list(firestore: &FirestoreDb, board_id: &String) -> Result<Vec<ActionItem>, Error> {
+  let mut object_stream: BoxStream<Option<ActionItemInFirestore>> = firestore
+    .fluent()
+    .list()
+    .from("action_items")
+    .parent(firestore.parent_path("boards", board_id)?)
+    .obj::<Option<ActionItemInFirestore>>()
+    .stream_all()
+    .await?;
+
+  let mut action_items: Vec<ActionItem> = vec![];
+  while let Some(Some(action_item)) = object_stream.next().await {
+    action_items.push(action_item.into());
+  }
+  Ok(action_items)
+}
+
+pub async fn get(
+  firestore: &FirestoreDb,
+  board_id: &String,
+  action_item_id: &String,
+) -> Result<ActionItem, Error> {
+  firestore
+    .fluent()
+    .select()
+    .by_id_in("action_items")
+    .parent(firestore.parent_path("boards", board_id)?)
+    .obj::<ActionItemInFirestore>()
+    .one(action_item_id)
+    .await?
+    .ok_or(Error::NotFound)
+    .map(|action_item| action_item.into())
+}
+
+pub async fn update(
+  firestore: &FirestoreDb,
+  board_id: &String,
+  action_item_id: &String,
+  action_item: ActionItemMessage,
+) -> Result<ActionItem, Error> {
+  let change_set = ActionItemChangeSet {
+    card: action_item
+      .card
+      .as_ref()
+      .map(|card_id| card_reference(firestore, board_id, card_id))
+      .transpose()?,
+    text: action_item.text,
+    assignee: action_item.assignee,
+    due_date: action_item.due_date,
+    status: action_item.status,
+  };
+  let serialised_action_item = serde_json::to_value(&change_set)?;
+  firestore
+    .fluent()
+    .update()
+    .fields(
+      paths!(ActionItemMessage::{text, assignee, due_date, status, card})
+        .into_iter()
+        .filter(|f| serialised_action_item.get(f).is_some()),
+    )
+    .in_col("action_items")
+    .document_id(action_item_id)
+    .parent(firestore.parent_path("boards", board_id)?)
+    .object(&change_set)
+    .execute::<ActionItemInFirestore>()
+    .await
+    .map(|action_item| action_item.into())
+    .map_err(|e| e.into())
+}
+
+pub async fn delete(
+  firestore: &FirestoreDb,
+  board_id: &String,
+  action_item_id: &String,
+) -> Result<(), Error> {
+  firestore
+    .fluent()
+    .delete()
+    .from("action_items")
+    .document_id(action_item_id)
+    .parent(firestore.parent_path("boards", board_id)?)
+    .execute()
+    .await
+    .map_err(|e| e.into())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::boards;
+  use crate::boards::models::BoardMessage;
+
+  // Run with: FIRESTORE_EMULATOR_HOST=localhost:8080 cargo test -- --ignored
+  async fn emulator_db() -> FirestoreDb {
+    use chrono::Utc;
+    use firestore::FirestoreDbOptions;
+    use gcloud_sdk::{ExternalJwtFunctionSource, Token, TokenSourceType};
+    // "owner" is the Firebase emulator's magic token that bypasses security rules,
+    // matching the credential used by Firebase Admin SDKs in emulator mode.
+    let token_source = ExternalJwtFunctionSource::new(|| async {
+      Ok(Token::new(
+        "Bearer".to_string(),
+        "owner".into(),
+        Utc::now() + chrono::Duration::hours(1),
+      ))
+    });
+    FirestoreDb::with_options_token_source(
+      FirestoreDbOptions::new("test-project".to_string()),
+      vec![],
+      TokenSourceType::ExternalSource(Box::new(token_source)),
+    )
+    .await
+    .unwrap()
+  }
+
+  fn test_participant() -> Participant {
+    Participant { id: "integration-test-participant".to_string() }
+  }
+
+  async fn setup_board(db: &FirestoreDb) -> String {
+    let retention = chrono::Duration::days(180);
+    boards::db::new(db, &test_participant(), BoardMessage::default(), retention)
+      .await
+      .unwrap()
+      .id
+  }
+
+  fn action_item_msg(text: &str) -> ActionItemMessage {
+    ActionItemMessage {
+      text: Some(text.to_string()),
+      ..Default::default()
+    }
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn new_action_item_can_be_retrieved_by_id() {
+    let db = emulator_db().await;
+    let board_id = setup_board(&db).await;
+    let created = new(&db, &test_participant(), &board_id, ActionItemMessage {
+      card: Some("c1".into()),
+      ..action_item_msg("Book a retro room")
+    })
+    .await
+    .unwrap();
+    let fetched = get(&db, &board_id, &created.id).await.unwrap();
+    assert_eq!(fetched.text, "Book a retro room");
+    assert_eq!(fetched.status, ActionItemStatus::Open);
+    assert_eq!(fetched.card_id(), Some("c1".to_string()));
+    boards::db::delete(&db, &board_id).await.unwrap();
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn insert_all_writes_every_action_item() {
+    let db = emulator_db().await;
+    let board_id = setup_board(&db).await;
+    let owner = FirestoreReference(format!("{}/participants/p1", db.get_documents_path()));
+    // More than a single batch can hold.
+    let count = MAX_BATCH_WRITES + 10;
+    let action_items = (0..count)
+      .map(|i| NewActionItem {
+        owner: Some(owner.clone()),
+        ..action_item_msg(&format!("Follow up {i:03}")).into()
+      })
+      .collect();
+    insert_all(&db, &board_id, action_items).await.unwrap();
+    let mut texts: Vec<String> =
+      list(&db, &board_id).await.unwrap().into_iter().map(|item| item.text).collect();
+    texts.sort();
+    assert_eq!(texts.len(), count);
+    assert_eq!(texts[0], "Follow up 000");
+    boards::db::delete(&db, &board_id).await.unwrap();
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn update_changes_only_given_fields() {
+    let db = emulator_db().await;
+    let board_id = setup_board(&db).await;
+    let created = new(&db, &test_participant(), &board_id, ActionItemMessage {
+      assignee: Some("Sam".into()),
+      ..action_item_msg("Write the runbook")
+    })
+    .await
+    .unwrap();
+    let updated = update(&db, &board_id, &created.id, ActionItemMessage {
+      status: Some(ActionItemStatus::Done),
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+    assert_eq!(updated.status, ActionItemStatus::Done);
+    assert_eq!(updated.text, "Write the runbook");
+    assert_eq!(updated.assignee, Some("Sam".to_string()));
+    boards::db::delete(&db, &board_id).await.unwrap();
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn delete_removes_action_item() {
+    let db = emulator_db().await;
+    let board_id = setup_board(&db).await;
+    let created = new(&db, &test_participant(), &board_id, action_item_msg("Gone soon")).await.unwrap();
+    delete(&db, &board_id, &created.id).await.unwrap();
+    assert!(matches!(get(&db, &board_id, &created.id).await, Err(Error::NotFound)));
+    assert!(list(&db, &board_id).await.unwrap().is_empty());
+    boards::db::delete(&db, &board_id).await.unwrap();
+  }
+}
@@ -0,0 +1,89 @@
+pub mod db;
+pub mod models;
+pub mod routes;
+
+use firestore::{FirestoreDb, FirestoreReference};
+
+use crate::boards;
+use crate::cards;
+use crate::columns::get_columns;
+use crate::error::Error;
+use crate::participants::models::Participant;
+
+pub async fn assert_action_item_owner(
+  firestore: &FirestoreDb,
+  participant: &Participant,
+  action_item: &models::ActionItem,
+  board_id: &String,
+) -> Result<(), Error> {
+  let board = boards::db::get(firestore, board_id).await?;
+  let participant_reference = FirestoreReference(format!(
+    "{}/participants/{}",
+    firestore.get_documents_path(),
+    participant.id
+  ));
+  if board.facilitated_by(&participant_reference)
+    || action_item.owner == participant_reference
+    || board.open_permission
+  {
+    Ok(())
+  } else {
+    Err(Error::Forbidden)
+  }
+}
+
+// Validates the message and makes sure any linked card exists on the board.
+async fn validate_action_item(
+  firestore: &FirestoreDb,
+  board_id: &String,
+  action_item_message: &models::ActionItemMessage,
+) -> Result<(), Error> {
+  action_item_message.validate()?;
+  if let Some(card_id) = &action_item_message.card {
+    cards::db::get(firestore, board_id, card_id).await?;
+  }
+  Ok(())
+}
+
+pub async fn assert_carry_over_allowed(
+  firestore: &FirestoreDb,
+  participant: &Participant,
+  previous_board_id: &String,
+  column_id: Option<&String>,
+) -> Result<(), Error> {
+  boards::assert_board_member(firestore, participant, previous_board_id).await?;
+  if let Some(column_id) = column_id {
+    if !get_columns(firestore, previous_board_id)
+      .await?
+      .contains_key(column_id)
+    {
+      return Err(Error::NotFound);
+    }
+  }
+  Ok(())
+}
+
+// Copies the open action items of a previous board, or the cards of one of its
+// columns when given, into a new board as open action items.
+pub async fn carry_over(
+  firestore: &FirestoreDb,
+  previous_board_id: &String,
+  column_id: Option<&String>,
+  board_id: &String,
+) -> Result<(), Error> {
+  let action_items: Vec<models::NewActionItem> = match column_id {
+    Some(column_id) => cards::db::list(firestore, previous_board_id)
+      .await?
+      .into_iter()
+      .filter(|card| card.column_id() == *column_id)
+      .map(|card| card.into())
+      .collect(),
+    None => db::list(firestore, previous_board_id)
+      .await?
+      .into_iter()
+      .filter(|action_item| action_item.status == models::ActionItemStatus::Open)
+      .map(|action_item| action_item.into())
+      .collect(),
+  };
+  db::insert_all(firestore, board_id, action_items).await
+}
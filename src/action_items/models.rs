@@ -0,0 +1,342 @@
+use chrono::{NaiveDate, Utc};
+use firestore::{FirestoreReference, FirestoreTimestamp};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::cards::models::Card;
+use crate::error::Error;
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionItemStatus {
+  #[default]
+  Open,
+  Done,
+}
+
+#[derive(Deserialize, Serialize, Default)]
+pub struct ActionItemMessage {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub text: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub assignee: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub due_date: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub status: Option<ActionItemStatus>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub card: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ActionItemChangeSet {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub text: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub assignee: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub due_date: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub status: Option<ActionItemStatus>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub card: Option<FirestoreReference>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ActionItem {
+  pub id: String,
+  pub owner: FirestoreReference,
+  pub text: String,
+  pub assignee: Option<String>,
+  pub due_date: Option<String>,
+  pub status: ActionItemStatus,
+  pub card: Option<FirestoreReference>,
+  pub created_at: i64,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ActionItemResponse {
+  pub id: String,
+  pub owner: bool,
+  pub text: String,
+  pub assignee: Option<String>,
+  pub due_date: Option<String>,
+  pub status: ActionItemStatus,
+  pub card: Option<String>,
+  pub created_at: i64,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct NewActionItem {
+  pub created_at: FirestoreTimestamp,
+  pub owner: Option<FirestoreReference>,
+  pub text: String,
+  pub assignee: Option<String>,
+  pub due_date: Option<String>,
+  pub status: ActionItemStatus,
+  pub card: Option<FirestoreReference>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ActionItemInFirestore {
+  pub _firestore_id: String,
+  pub _firestore_created: FirestoreTimestamp,
+  pub created_at: Option<FirestoreTimestamp>,
+  pub owner: FirestoreReference,
+  pub text: String,
+  pub assignee: Option<String>,
+  pub due_date: Option<String>,
+  pub status: Option<ActionItemStatus>,
+  pub card: Option<FirestoreReference>,
+}
+
+#[derive(Serialize)]
+pub struct ActionItemCSVRow {
+  pub action_item: String,
+  pub assignee: String,
+  pub due_date: String,
+  pub status: ActionItemStatus,
+  pub card: String,
+}
+
+impl ActionItemMessage {
+  pub fn validate(&self) -> Result<(), Error> {
+    if self.text.as_ref().is_some_and(|text| text.is_empty()) {
+      return Err(Error::BadRequest("Empty action items are not allowed.".into()));
+    }
+    match &self.due_date {
+      Some(due_date) if NaiveDate::parse_from_str(due_date, "%Y-%m-%d").is_err() => Err(
+        Error::BadRequest("Due dates must be formatted as YYYY-MM-DD.".into()),
+      ),
+      _ => Ok(()),
+    }
+  }
+}
+
+impl From<ActionItemMessage> for NewActionItem {
+  // The source card is resolved to a reference by the caller, as it needs the board path.
+  fn from(action_item: ActionItemMessage) -> Self {
+    NewActionItem {
+      created_at: FirestoreTimestamp(Utc::now()),
+      owner: None,
+      text: action_item.text.unwrap_or_default(),
+      assignee: action_item.assignee,
+      due_date: action_item.due_date,
+      status: action_item.status.unwrap_or_default(),
+      card: None,
+    }
+  }
+}
+
+// Carried over actions keep their owner so they can still be edited, but lose
+// their card link, as the source card stays behind on the previous board.
+impl From<ActionItem> for NewActionItem {
+  fn from(action_item: ActionItem) -> Self {
+    NewActionItem {
+      created_at: FirestoreTimestamp(Utc::now()),
+      owner: Some(action_item.owner),
+      text: action_item.text,
+      assignee: action_item.assignee,
+      due_date: action_item.due_date,
+      status: ActionItemStatus::Open,
+      card: None,
+    }
+  }
+}
+
+impl From<Card> for NewActionItem {
+  fn from(card: Card) -> Self {
+    NewActionItem {
+      created_at: FirestoreTimestamp(Utc::now()),
+      owner: Some(card.owner),
+      text: card.text,
+      assignee: None,
+      due_date: None,
+      status: ActionItemStatus::Open,
+      card: None,
+    }
+  }
+}
+
+impl From<ActionItemInFirestore> for ActionItem {
+  fn from(action_item: ActionItemInFirestore) -> Self {
+    ActionItem {
+      id: action_item._firestore_id,
+      owner: action_item.owner,
+      text: action_item.text,
+      assignee: action_item.assignee,
+      due_date: action_item.due_date,
+      status: action_item.status.unwrap_or_default(),
+      card: action_item.card,
+      created_at: action_item
+        .created_at
+        .unwrap_or(action_item._firestore_created)
+        .0
+        .timestamp(),
+    }
+  }
+}
+
+impl ActionItem {
+  pub fn card_id(&self) -> Option<String> {
+    self
+      .card
+      .as_ref()
+      .map(|card| card.0.split('/').next_back().unwrap().to_string())
+  }
+}
+
+impl ActionItemResponse {
+  pub fn from_action_item(
+    action_item: ActionItem,
+    participant_id: &FirestoreReference,
+  ) -> ActionItemResponse {
+    ActionItemResponse {
+      card: action_item.card_id(),
+      id: action_item.id,
+      owner: &action_item.owner == participant_id,
+      text: action_item.text,
+      assignee: action_item.assignee,
+      due_date: action_item.due_date,
+      status: action_item.status,
+      created_at: action_item.created_at,
+    }
+  }
+}
+
+impl ActionItemCSVRow {
+  pub fn from_action_item(
+    action_item: ActionItem,
+    card_texts: &HashMap<String, String>,
+  ) -> ActionItemCSVRow {
+    ActionItemCSVRow {
+      card: action_item
+        .card_id()
+        .and_then(|card_id| card_texts.get(&card_id).cloned())
+        .unwrap_or_default(),
+      action_item: action_item.text,
+      assignee: action_item.assignee.unwrap_or_default(),
+      due_date: action_item.due_date.unwrap_or_default(),
+      status: action_item.status,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn ref_(path: &str) -> FirestoreReference {
+    FirestoreReference(path.to_string())
+  }
+
+  fn make_action_item(card: Option<&str>) -> ActionItem {
+    ActionItem {
+      id: "a1".to_string(),
+      owner: ref_("participants/user1"),
+      text: "Fix the flaky tests".to_string(),
+      assignee: Some("Sam".to_string()),
+      due_date: Some("2026-11-01".to_string()),
+      status: ActionItemStatus::Open,
+      card: card.map(ref_),
+      created_at: 1_000_000,
+    }
+  }
+
+  #[test]
+  fn message_with_valid_due_date_is_valid() {
+    let msg = ActionItemMessage {
+      text: Some("Do it".into()),
+      due_date: Some("2026-11-01".into()),
+      ..Default::default()
+    };
+    assert!(msg.validate().is_ok());
+  }
+
+  #[test]
+  fn message_with_malformed_due_date_is_bad_request() {
+    let msg = ActionItemMessage {
+      text: Some("Do it".into()),
+      due_date: Some("next friday".into()),
+      ..Default::default()
+    };
+    assert!(matches!(msg.validate(), Err(Error::BadRequest(_))));
+  }
+
+  #[test]
+  fn message_with_empty_text_is_bad_request() {
+    let msg = ActionItemMessage {
+      text: Some("".into()),
+      ..Default::default()
+    };
+    assert!(matches!(msg.validate(), Err(Error::BadRequest(_))));
+  }
+
+  #[test]
+  fn new_action_item_defaults_to_open() {
+    let item: NewActionItem = ActionItemMessage {
+      text: Some("Do it".into()),
+      ..Default::default()
+    }
+    .into();
+    assert_eq!(item.status, ActionItemStatus::Open);
+    assert!(item.assignee.is_none());
+  }
+
+  #[test]
+  fn carried_over_action_item_keeps_owner_and_drops_card() {
+    let item: NewActionItem = make_action_item(Some("boards/b1/cards/c9")).into();
+    assert_eq!(item.owner, Some(ref_("participants/user1")));
+    assert_eq!(item.text, "Fix the flaky tests");
+    assert_eq!(item.assignee, Some("Sam".to_string()));
+    assert!(item.card.is_none());
+  }
+
+  #[test]
+  fn card_becomes_open_action_item() {
+    let card = Card {
+      id: "c1".into(),
+      column: ref_("boards/b1/columns/col1"),
+      owner: ref_("participants/user2"),
+      author: "Alex".into(),
+      text: "Automate releases".into(),
+      created_at: 1_000_000,
+      votes: vec![],
+      vote_counts: HashMap::new(),
+      reactions: HashMap::new(),
+      parent: None,
+    };
+    let item: NewActionItem = card.into();
+    assert_eq!(item.owner, Some(ref_("participants/user2")));
+    assert_eq!(item.text, "Automate releases");
+    assert_eq!(item.status, ActionItemStatus::Open);
+  }
+
+  #[test]
+  fn status_serialises_as_snake_case() {
+    assert_eq!(serde_json::to_value(ActionItemStatus::Done).unwrap(), "done");
+  }
+
+  #[test]
+  fn response_exposes_card_id_and_ownership() {
+    let item = make_action_item(Some("projects/p/databases/d/documents/boards/b1/cards/c9"));
+    let resp = ActionItemResponse::from_action_item(item, &ref_("participants/user1"));
+    assert_eq!(resp.card, Some("c9".to_string()));
+    assert!(resp.owner);
+  }
+
+  #[test]
+  fn csv_row_uses_source_card_text() {
+    let item = make_action_item(Some("boards/b1/cards/c9"));
+    let card_texts = HashMap::from([("c9".to_string(), "Tests are flaky".to_string())]);
+    let row = ActionItemCSVRow::from_action_item(item, &card_texts);
+    assert_eq!(row.card, "Tests are flaky");
+    assert_eq!(row.assignee, "Sam");
+  }
+
+  #[test]
+  fn csv_row_without_card_is_blank() {
+    let row = ActionItemCSVRow::from_action_item(make_action_item(None), &HashMap::new());
+    assert_eq!(row.card, "");
+  }
+}
@@ -0,0 +1,139 @@
+use actix_web::{delete, get, patch, post, web, HttpResponse};
+use firestore::{FirestoreDb, FirestoreReference};
+
+use super::db;
+use super::models::*;
+use crate::boards::*;
+use crate::error::Error;
+use crate::events::models::BoardEvent;
+use crate::events::Broker;
+use crate::participants::models::Participant;
+
+#[post("boards/{board_id}/action_items")]
+pub async fn new(
+  firestore: web::Data<FirestoreDb>,
+  broker: web::Data<Broker>,
+  participant: Participant,
+  board_id: web::Path<String>,
+  action_item_message: web::Json<ActionItemMessage>,
+) -> Result<HttpResponse, Error> {
+  assert_board_access(&firestore, &participant, &board_id).await?;
+  let action_item_message = action_item_message.into_inner();
+  if action_item_message.text.is_none() {
+    return Err(Error::BadRequest("Action item text must be provided.".into()));
+  }
+  super::validate_action_item(&firestore, &board_id, &action_item_message).await?;
+  let action_item = db::new(&firestore, &participant, &board_id, action_item_message).await?;
+  broker.publish(
+    &board_id,
+    BoardEvent::ActionItemChanged {
+      action_item_id: action_item.id.clone(),
+    },
+  );
+  Ok(
+    HttpResponse::Ok().json(ActionItemResponse::from_action_item(
+      action_item,
+      &FirestoreReference(
+        firestore
+          .parent_path("participants", &participant.id)
+          .unwrap()
+          .into(),
+      ),
+    )),
+  )
+}
+
+#[get("boards/{board_id}/action_items")]
+pub async fn list(
+  firestore: web::Data<FirestoreDb>,
+  participant: Participant,
+  board_id: web::Path<String>,
+) -> Result<HttpResponse, Error> {
+  assert_board_access(&firestore, &participant, &board_id).await?;
+  let action_items = db::list(&firestore, &board_id).await?;
+  Ok(
+    HttpResponse::Ok().json(
+      action_items
+        .into_iter()
+        .map(|action_item| {
+          ActionItemResponse::from_action_item(
+            action_item,
+            &FirestoreReference(
+              firestore
+                .parent_path("participants", &participant.id)
+                .unwrap()
+                .into(),
+            ),
+          )
+        })
+        .collect::<Vec<ActionItemResponse>>(),
+    ),
+  )
+}
+
+#[get("boards/{board_id}/action_items/{action_item_id}")]
+pub async fn get(
+  firestore: web::Data<FirestoreDb>,
+  participant: Participant,
+  params: web::Path<(String, String)>,
+) -> Result<HttpResponse, Error> {
+  let (board_id, action_item_id) = params.into_inner();
+  assert_board_access(&firestore, &participant, &board_id).await?;
+  let action_item = db::get(&firestore, &board_id, &action_item_id).await?;
+  Ok(
+    HttpResponse::Ok().json(ActionItemResponse::from_action_item(
+      action_item,
+      &FirestoreReference(
+        firestore
+          .parent_path("participants", &participant.id)
+          .unwrap()
+          .into(),
+      ),
+    )),
+  )
+}
+
+#[patch("boards/{board_id}/action_items/{action_item_id}")]
+pub async fn update(
+  firestore: web::Data<FirestoreDb>,
+  broker: web::Data<Broker>,
+  participant: Participant,
+  params: web::Path<(String, String)>,
+  action_item_message: web::Json<ActionItemMessage>,
+) -> Result<HttpResponse, Error> {
+  let (board_id, action_item_id) = params.into_inner();
+  assert_board_access(&firestore, &participant, &board_id).await?;
+  let action_item = db::get(&firestore, &board_id, &action_item_id).await?;
+  super::assert_action_item_owner(&firestore, &participant, &action_item, &board_id).await?;
+  let action_item_message = action_item_message.into_inner();
+  super::validate_action_item(&firestore, &board_id, &action_item_message).await?;
+  let action_item = db::update(&firestore, &board_id, &action_item_id, action_item_message).await?;
+  broker.publish(&board_id, BoardEvent::ActionItemChanged { action_item_id });
+  Ok(
+    HttpResponse::Ok().json(ActionItemResponse::from_action_item(
+      action_item,
+      &FirestoreReference(
+        firestore
+          .parent_path("participants", &participant.id)
+          .unwrap()
+          .into(),
+      ),
+    )),
+  )
+}
+
+#[delete("boards/{board_id}/action_items/{action_item_id}")]
+pub async fn delete(
+  firestore: web::Data<FirestoreDb>,
+  broker: web::Data<Broker>,
+  participant: Participant,
+  params: web::Path<(String, String)>,
+) -> Result<HttpResponse, Error> {
+  let (board_id, action_item_id) = params.into_inner();
+  assert_board_access(&firestore, &participant, &board_id).await?;
+  let action_item = db::get(&firestore, &board_id, &action_item_id).await?;
+  super::assert_action_item_owner(&firestore, &participant, &action_item, &board_id).await?;
+  db::delete(&firestore, &board_id, &action_item_id).await?;
+  broker.publish(&board_id, BoardEvent::ActionItemChanged { action_item_id });
+  Ok(HttpResponse::Ok().finish())
+}
@@ -0,0 +1,414 @@
+use firestore::path;
+use firestore::FirestoreDb;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::action_items;
+use crate::action_items::models::ActionItem;
+use crate::boards;
+use crate::boards::models::{Board, BoardInFirestore};
+use crate::cards;
+use crate::cards::models::Card;
+use crate::columns;
+use crate::columns::models::Column;
+use crate::error::Error;
+use crate::participants;
+
+// Subcollection documents left behind under a board that no longer exists.
+#[derive(Serialize, Debug, Default, PartialEq)]
+pub struct Orphans {
+  pub board_id: String,
+  pub cards: usize,
+  pub columns: usize,
+  pub action_items: usize,
+  pub members: usize,
+}
+
+#[derive(Serialize)]
+pub struct BoardExport {
+  pub board: Board,
+  pub columns: Vec<Column>,
+  pub cards: Vec<Card>,
+  pub action_items: Vec<ActionItem>,
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct Stats {
+  pub boards: usize,
+  pub deleted_boards: usize,
+  pub columns: usize,
+  pub cards: usize,
+  pub action_items: usize,
+  pub participants: usize,
+  pub templates: usize,
+}
+
+#[derive(Deserialize)]
+struct Count {
+  count: usize,
+}
+
+// Document names look like "projects/p/databases/d/documents/boards/{board_id}/cards/{id}".
+fn parent_board_id(document_name: &str) -> Option<&str> {
+  let mut segments = document_name
+    .split('/')
+    .skip_while(|s| *s != "boards")
+    .skip(1);
+  segments.next()
+}
+
+// Walks every board subcollection and groups its documents by the board they
+// belong to, keeping only those whose board is gone. Trashed boards still exist
+// and so aren't orphans.
+pub async fn find_orphans(firestore: &FirestoreDb) -> Result<Vec<Orphans>, Error> {
+  let mut by_board: BTreeMap<String, Orphans> = BTreeMap::new();
+  for collection in boards::db::BOARD_SUBCOLLECTIONS {
+    let documents = firestore
+      .fluent()
+      .select()
+      .fields(["__name__"])
+      .from(collection)
+      .all_descendants()
+      .query()
+      .await?;
+    for document in documents {
+      let Some(board_id) = parent_board_id(&document.name) else {
+        continue;
+      };
+      let orphans = by_board
+        .entry(board_id.to_string())
+        .or_insert_with(|| Orphans {
+          board_id: board_id.to_string(),
+          ..Default::default()
+        });
+      match collection {
+        "cards" => orphans.cards += 1,
+        "columns" => orphans.columns += 1,
+        "members" => orphans.members += 1,
+        _ => orphans.action_items += 1,
+      }
+    }
+  }
+
+  let mut orphans = vec![];
+  for (board_id, documents) in by_board {
+    match boards::db::get_including_deleted(firestore, &board_id).await {
+      Ok(_) => {}
+      Err(Error::NotFound) => orphans.push(documents),
+      Err(e) => return Err(e),
+    }
+  }
+  Ok(orphans)
+}
+
+// Deleting the missing board clears its subcollections along with any
+// legacy participant `boards` arrays still pointing at it.
+pub async fn delete_orphans(firestore: &FirestoreDb, orphans: &[Orphans]) -> Result<(), Error> {
+  for orphan in orphans {
+    boards::db::delete(firestore, &orphan.board_id).await?;
+  }
+  Ok(())
+}
+
+pub async fn export_board(
+  firestore: &FirestoreDb,
+  board_id: &String,
+) -> Result<BoardExport, Error> {
+  let board = boards::db::get_including_deleted(firestore, board_id).await?;
+  let mut columns = columns::db::list(firestore, board_id).await?;
+  columns.sort_by_key(|column| (column.position, column.created_at));
+  let mut cards = cards::db::list(firestore, board_id).await?;
+  cards.sort_by_key(|card| card.created_at);
+  let mut action_items = action_items::db::list(firestore, board_id).await?;
+  action_items.sort_by_key(|action_item| action_item.created_at);
+  Ok(BoardExport {
+    board,
+    columns,
+    cards,
+    action_items,
+  })
+}
+
+// Hard deletes a board, trashed or not, skipping the restore window.
+pub async fn delete_board(firestore: &FirestoreDb, board_id: &String) -> Result<(), Error> {
+  boards::db::get_including_deleted(firestore, board_id).await?;
+  boards::db::delete(firestore, board_id).await
+}
+
+// Moves the boards array each participant used to keep onto member documents and
+// then drops the array. Boards that no longer exist are skipped, and running it
+// again only picks up participants that still have an array. Returns the number of
+// participants migrated.
+pub async fn migrate_members(firestore: &FirestoreDb) -> Result<usize, Error> {
+  let legacy = participants::db::list_legacy_participants(firestore).await?;
+  for participant in &legacy {
+    participants::db::adopt_legacy_boards(firestore, participant).await?;
+  }
+  Ok(legacy.len())
+}
+
+async fn count(
+  firestore: &FirestoreDb,
+  collection: &str,
+  all_descendants: bool,
+) -> Result<usize, Error> {
+  let query = firestore.fluent().select().from(collection);
+  let query = if all_descendants {
+    query.all_descendants()
+  } else {
+    query
+  };
+  let counts: Vec<Count> = query
+    .aggregate(|a| a.fields([a.field(path!(Count::count)).count()]))
+    .obj()
+    .query()
+    .await?;
+  Ok(counts.first().map(|c| c.count).unwrap_or(0))
+}
+
+async fn count_deleted_boards(firestore: &FirestoreDb) -> Result<usize, Error> {
+  let counts: Vec<Count> = firestore
+    .fluent()
+    .select()
+    .from("boards")
+    .filter(|q| q.for_all([q.field(path!(BoardInFirestore::deleted_at)).is_not_null()]))
+    .aggregate(|a| a.fields([a.field(path!(Count::count)).count()]))
+    .obj()
+    .query()
+    .await?;
+  Ok(counts.first().map(|c| c.count).unwrap_or(0))
+}
+
+pub async fn stats(firestore: &FirestoreDb) -> Result<Stats, Error> {
+  Ok(Stats {
+    boards: count(firestore, "boards", false).await?,
+    deleted_boards: count_deleted_boards(firestore).await?,
+    columns: count(firestore, "columns", true).await?,
+    cards: count(firestore, "cards", true).await?,
+    action_items: count(firestore, "action_items", true).await?,
+    participants: count(firestore, "participants", false).await?,
+    templates: count(firestore, "templates", false).await?,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::boards::models::BoardMessage;
+  use crate::cards::models::CardMessage;
+  use crate::columns::models::ColumnMessage;
+  use crate::participants::models::Participant;
+
+  // Run with: FIRESTORE_EMULATOR_HOST=localhost:8080 cargo test -- --ignored
+  async fn emulator_db() -> FirestoreDb {
+    use chrono::Utc;
+    use firestore::FirestoreDbOptions;
+    use gcloud_sdk::{ExternalJwtFunctionSource, Token, TokenSourceType};
+    // "owner" is the Firebase emulator's magic token that bypasses security rules,
+    // matching the credential used by Firebase Admin SDKs in emulator mode.
+    let token_source = ExternalJwtFunctionSource::new(|| async {
+      Ok(Token::new(
+        "Bearer".to_string(),
+        "owner".into(),
+        Utc::now() + chrono::Duration::hours(1),
+      ))
+    });
+    FirestoreDb::with_options_token_source(
+      FirestoreDbOptions::new("test-project".to_string()),
+      vec![],
+      TokenSourceType::ExternalSource(Box::new(token_source)),
+    )
+    .await
+    .unwrap()
+  }
+
+  fn participant() -> Participant {
+    Participant {
+      id: "admin-test-participant".to_string(),
+    }
+  }
+
+  async fn board_with_card(db: &FirestoreDb, name: &str) -> (Board, Column) {
+    let board = boards::db::new(
+      db,
+      &participant(),
+      BoardMessage {
+        name: Some(name.into()),
+        ..Default::default()
+      },
+      chrono::Duration::days(180),
+    )
+    .await
+    .unwrap();
+    let column = columns::db::new(
+      db,
+      &board.id,
+      ColumnMessage {
+        name: Some("Start".into()),
+        data: None,
+        position: Some(0),
+      },
+    )
+    .await
+    .unwrap();
+    cards::db::new(
+      db,
+      &participant(),
+      &board.id,
+      CardMessage {
+        author: None,
+        text: Some("A card".into()),
+        column: Some(format!(
+          "{}/boards/{}/columns/{}",
+          db.get_documents_path(),
+          board.id,
+          column.id
+        )),
+      },
+    )
+    .await
+    .unwrap();
+    (board, column)
+  }
+
+  // Leaves the board's subcollections behind, as boards deleted before
+  // cascading deletes did.
+  async fn delete_board_document(db: &FirestoreDb, board_id: &String) {
+    db.fluent()
+      .delete()
+      .from("boards")
+      .document_id(board_id)
+      .execute()
+      .await
+      .unwrap();
+  }
+
+  #[test]
+  fn parent_board_id_is_read_from_document_name() {
+    assert_eq!(
+      parent_board_id("projects/p/databases/(default)/documents/boards/b1/cards/c1"),
+      Some("b1")
+    );
+    assert_eq!(
+      parent_board_id("projects/p/databases/(default)/documents/templates/t1"),
+      None
+    );
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn orphans_are_found_and_deleted() {
+    let db = emulator_db().await;
+    let (orphaned, _) = board_with_card(&db, "Orphaned").await;
+    let (kept, _) = board_with_card(&db, "Kept").await;
+    delete_board_document(&db, &orphaned.id).await;
+
+    let orphans = find_orphans(&db).await.unwrap();
+    let found = orphans.iter().find(|o| o.board_id == orphaned.id).unwrap();
+    assert_eq!((found.cards, found.columns, found.action_items), (1, 1, 0));
+    assert!(orphans.iter().all(|o| o.board_id != kept.id));
+
+    delete_orphans(&db, &orphans).await.unwrap();
+    assert!(cards::db::list(&db, &orphaned.id).await.unwrap().is_empty());
+    assert!(columns::db::list(&db, &orphaned.id)
+      .await
+      .unwrap()
+      .is_empty());
+    assert_eq!(cards::db::list(&db, &kept.id).await.unwrap().len(), 1);
+    boards::db::delete(&db, &kept.id).await.unwrap();
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn export_includes_columns_and_cards() {
+    let db = emulator_db().await;
+    let (board, column) = board_with_card(&db, "Exported").await;
+    let export = export_board(&db, &board.id).await.unwrap();
+    assert_eq!(export.board.name, "Exported");
+    assert_eq!(export.columns.len(), 1);
+    assert_eq!(export.columns[0].id, column.id);
+    assert_eq!(export.cards.len(), 1);
+    assert_eq!(export.cards[0].text, "A card");
+    assert!(export.action_items.is_empty());
+    boards::db::delete(&db, &board.id).await.unwrap();
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn delete_board_removes_trashed_boards_and_rejects_unknown_ones() {
+    let db = emulator_db().await;
+    let (board, _) = board_with_card(&db, "Deleted").await;
+    boards::db::move_to_trash(&db, &board.id).await.unwrap();
+    delete_board(&db, &board.id).await.unwrap();
+    assert!(matches!(
+      boards::db::get_including_deleted(&db, &board.id).await,
+      Err(Error::NotFound)
+    ));
+    assert!(matches!(
+      delete_board(&db, &board.id).await,
+      Err(Error::NotFound)
+    ));
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn stats_count_boards_and_their_contents() {
+    let db = emulator_db().await;
+    let (board, _) = board_with_card(&db, "Counted").await;
+    boards::db::move_to_trash(&db, &board.id).await.unwrap();
+    let counted = stats(&db).await.unwrap();
+    assert!(counted.boards >= 1);
+    assert!(counted.deleted_boards >= 1);
+    assert!(counted.boards >= counted.deleted_boards);
+    assert!(counted.columns >= 1);
+    assert!(counted.cards >= 1);
+    boards::db::delete(&db, &board.id).await.unwrap();
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn migrate_members_moves_boards_arrays_onto_member_documents() {
+    use crate::participants::models::ParticipantInFirestore;
+    use firestore::FirestoreReference;
+
+    #[derive(Serialize, Deserialize)]
+    struct LegacyParticipant {
+      boards: Vec<FirestoreReference>,
+    }
+
+    let db = emulator_db().await;
+    let (board, _) = board_with_card(&db, "Migrated").await;
+    let legacy = Participant {
+      id: "admin-test-legacy-participant".to_string(),
+    };
+    let boards = vec![
+      FirestoreReference(format!("{}/boards/{}", db.get_documents_path(), board.id)),
+      FirestoreReference(format!("{}/boards/missing-board", db.get_documents_path())),
+    ];
+    db.fluent()
+      .update()
+      .in_col("participants")
+      .document_id(&legacy.id)
+      .object(&LegacyParticipant { boards })
+      .execute::<LegacyParticipant>()
+      .await
+      .unwrap();
+
+    assert!(migrate_members(&db).await.unwrap() >= 1);
+    assert_eq!(
+      participants::db::get_participant_board_ids(&db, &legacy).await.unwrap(),
+      vec![board.id.clone()]
+    );
+    let migrated: ParticipantInFirestore = db
+      .fluent()
+      .select()
+      .by_id_in("participants")
+      .obj()
+      .one(&legacy.id)
+      .await
+      .unwrap()
+      .unwrap();
+    assert!(migrated.boards.is_none());
+    boards::db::delete(&db, &board.id).await.unwrap();
+  }
+}
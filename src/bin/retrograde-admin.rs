@@ -0,0 +1,136 @@
+use clap::{Parser, Subcommand};
+use firestore::FirestoreDb;
+use std::process::ExitCode;
+
+use retrograde::admin;
+use retrograde::boards;
+use retrograde::config::FirestoreConfig;
+use retrograde::error::Error;
+
+// Maintenance tasks run by hand against the same Firestore project as the server,
+// configured through the same environment. Only the Firestore settings are needed.
+#[derive(Parser)]
+#[command(name = "retrograde-admin", about = "Maintenance tasks for retro.tools")]
+struct Cli {
+  #[command(subcommand)]
+  command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+  /// Delete cards, columns, action items and members whose board no longer exists
+  Orphans {
+    /// Only report the orphans that would be deleted
+    #[arg(long)]
+    dry_run: bool,
+  },
+  /// Delete expired boards and boards left in the trash past the restore window
+  PurgeExpired,
+  /// Manage a single board
+  Board {
+    #[command(subcommand)]
+    command: BoardCommand,
+  },
+  /// Count the documents in each collection
+  Stats,
+  /// Move participants' legacy boards arrays onto board member documents
+  MigrateMembers,
+}
+
+#[derive(Subcommand)]
+enum BoardCommand {
+  /// Print the board with its columns, cards and action items as JSON
+  Export { id: String },
+  /// Permanently delete the board, skipping the trash
+  Delete { id: String },
+}
+
+async fn run(command: Command, config: &FirestoreConfig) -> Result<(), Error> {
+  let firestore = FirestoreDb::new(config.firestore_project.clone()).await?;
+  match command {
+    Command::Orphans { dry_run } => {
+      let orphans = admin::find_orphans(&firestore).await?;
+      for orphan in &orphans {
+        println!(
+          "{}: {} cards, {} columns, {} action items, {} members",
+          orphan.board_id, orphan.cards, orphan.columns, orphan.action_items, orphan.members
+        );
+      }
+      if dry_run {
+        println!("Found orphans under {} boards.", orphans.len());
+      } else {
+        admin::delete_orphans(&firestore, &orphans).await?;
+        println!("Deleted orphans under {} boards.", orphans.len());
+      }
+    }
+    Command::PurgeExpired => {
+      let deleted = boards::db::purge_deleted(&firestore, config.board_restore_window).await?;
+      let expired = boards::db::purge_expired(&firestore).await?;
+      println!("Purged {} deleted and {} expired boards.", deleted, expired);
+    }
+    Command::Board {
+      command: BoardCommand::Export { id },
+    } => {
+      let export = admin::export_board(&firestore, &id).await?;
+      println!("{}", serde_json::to_string_pretty(&export)?);
+    }
+    Command::Board {
+      command: BoardCommand::Delete { id },
+    } => {
+      admin::delete_board(&firestore, &id).await?;
+      println!("Deleted board {}.", id);
+    }
+    Command::Stats => {
+      let stats = admin::stats(&firestore).await?;
+      println!("{}", serde_json::to_string_pretty(&stats)?);
+    }
+    Command::MigrateMembers => {
+      let migrated = admin::migrate_members(&firestore).await?;
+      println!("Migrated memberships for {} participants.", migrated);
+    }
+  }
+  Ok(())
+}
+
+#[actix_web::main]
+async fn main() -> ExitCode {
+  env_logger::init();
+  dotenv::dotenv().ok();
+
+  let cli = Cli::parse();
+  let config = FirestoreConfig::from_env();
+  match run(cli.command, &config).await {
+    Ok(()) => ExitCode::SUCCESS,
+    Err(e) => {
+      eprintln!("{}", e);
+      ExitCode::FAILURE
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use clap::CommandFactory;
+
+  #[test]
+  fn cli_is_well_formed() {
+    Cli::command().debug_assert();
+  }
+
+  #[test]
+  fn orphans_accepts_dry_run() {
+    let cli = Cli::try_parse_from(["retrograde-admin", "orphans", "--dry-run"]).unwrap();
+    assert!(matches!(cli.command, Command::Orphans { dry_run: true }));
+  }
+
+  #[test]
+  fn board_subcommands_take_an_id() {
+    let cli = Cli::try_parse_from(["retrograde-admin", "board", "export", "b1"]).unwrap();
+    assert!(matches!(
+      cli.command,
+      Command::Board { command: BoardCommand::Export { id } } if id == "b1"
+    ));
+    assert!(Cli::try_parse_from(["retrograde-admin", "board", "delete"]).is_err());
+  }
+}
@@ -1,64 +1,685 @@
+use chrono::{Duration, Utc};
+use firestore::errors::FirestoreError;
+use firestore::path;
 use firestore::paths;
 use firestore::FirestoreDb;
 use firestore::FirestoreReference;
+use firestore::FirestoreTimestamp;
+use firestore::FirestoreTransaction;
+use firestore::FirestoreWritePrecondition;
 use futures::stream::BoxStream;
 use futures::StreamExt;
+use rand::distr::Alphanumeric;
+use rand::Rng;
+use std::collections::HashMap;
 
 use super::models::*;
-use crate::error::Error;
-use crate::participants::db::get_participant_board_ids;
+use crate::cards::models::{Card, ClonedCard};
+use crate::columns::models::{Column, ColumnMessage, NewColumn};
+use crate::error::{check_batch, Error};
+use crate::participants::db::{
+  adopt_legacy_boards, list_participant_board_positions, remove_board_from_participants,
+  set_member_role,
+};
 use crate::participants::models::Participant;
 
 pub async fn new(
   firestore: &FirestoreDb,
   participant: &Participant,
   board: BoardMessage,
+  retention: Duration,
 ) -> Result<Board, Error> {
   let mut new_board: NewBoard = board.into();
+  new_board.expire_at = Some(FirestoreTimestamp(Utc::now() + retention));
   new_board.owner = Some(FirestoreReference(format!(
     "{}/participants/{}",
     firestore.get_documents_path(),
     participant.id
   )));
+  create(firestore, &generate_document_id(), new_board, |_| Ok(())).await
+}
+
+// Mirrors the 20 character IDs Firestore generates, for documents that have to
+// be created inside a transaction, where inserts with generated IDs aren't available.
+pub fn generate_document_id() -> String {
+  rand::rng()
+    .sample_iter(&Alphanumeric)
+    .take(20)
+    .map(char::from)
+    .collect()
+}
+
+const JOIN_CODE_WORDS: [&str; 64] = [
+  "ANT", "BAT", "BEAR", "BEE", "BISON", "BOAR", "CAMEL", "CAT", "CLAM", "COBRA", "COD", "CRAB",
+  "CRANE", "CROW", "DEER", "DINGO", "DOVE", "DUCK", "EAGLE", "EEL", "ELK", "EMU", "FINCH", "FOX",
+  "FROG", "GECKO", "GOAT", "GOOSE", "HARE", "HAWK", "HERON", "HIPPO", "HORSE", "IBIS", "KOALA",
+  "KOI", "LARK", "LEMUR", "LION", "LLAMA", "LYNX", "MOLE", "MOOSE", "MOTH", "MOUSE", "NEWT",
+  "OTTER", "OWL", "PANDA", "PIKE", "PUMA", "RAVEN", "ROBIN", "SEAL", "SHARK", "SNAIL", "SQUID",
+  "SWAN", "TIGER", "TOAD", "TROUT", "WHALE", "WOLF", "YAK",
+];
+
+// A taken code fails the commit that claims it, so it's retried a few times with a
+// fresh code before giving up.
+const JOIN_CODE_ATTEMPTS: usize = 5;
+
+// Codes like FOX-482193 are meant to be read out on a call, so they trade the size
+// of a document ID for words and digits that are hard to mishear. There are still
+// tens of millions of them, and failed lookups are rate limited on top.
+pub fn generate_join_code() -> String {
+  let mut rng = rand::rng();
+  format!(
+    "{}-{}",
+    JOIN_CODE_WORDS[rng.random_range(0..JOIN_CODE_WORDS.len())],
+    rng.random_range(100_000..1_000_000)
+  )
+}
+
+// Codes are typed back in by hand, so case and stray whitespace are ignored.
+pub fn normalise_join_code(join_code: &str) -> String {
+  join_code.trim().to_uppercase()
+}
+
+// Creates the board, its columns and its join code in a single transaction, so a
+// failure can't leave a partial board behind.
+pub async fn new_with_columns(
+  firestore: &FirestoreDb,
+  participant: &Participant,
+  board: BoardMessage,
+  columns: Vec<ColumnMessage>,
+  retention: Duration,
+) -> Result<Board, Error> {
+  let mut new_board: NewBoard = board.into();
+  new_board.expire_at = Some(FirestoreTimestamp(Utc::now() + retention));
+  new_board.owner = Some(FirestoreReference(format!(
+    "{}/participants/{}",
+    firestore.get_documents_path(),
+    participant.id
+  )));
+  let board_id = generate_document_id();
+  let board_path = firestore.parent_path("boards", &board_id)?;
+  let new_columns: Vec<(String, NewColumn)> = columns
+    .into_iter()
+    .map(|column| (generate_document_id(), column.into()))
+    .collect();
+
+  create(firestore, &board_id, new_board, |transaction| {
+    for (column_id, new_column) in &new_columns {
+      firestore
+        .fluent()
+        .update()
+        .in_col("columns")
+        .precondition(FirestoreWritePrecondition::Exists(false))
+        .document_id(column_id)
+        .parent(&board_path)
+        .object(new_column)
+        .add_to_transaction(transaction)?;
+    }
+    Ok(())
+  })
+  .await
+}
+
+// Writes a new board with a fresh join code, plus whatever `add_writes` adds to the
+// transaction, in a single commit. A code that's already taken fails the commit, so
+// the whole transaction is retried with another.
+async fn create(
+  firestore: &FirestoreDb,
+  board_id: &String,
+  mut board: NewBoard,
+  add_writes: impl Fn(&mut FirestoreTransaction<'_>) -> Result<(), Error>,
+) -> Result<Board, Error> {
+  for _ in 0..JOIN_CODE_ATTEMPTS {
+    let join_code = generate_join_code();
+    board.join_code = Some(join_code.clone());
+    let mut transaction = firestore.begin_transaction().await?;
+    firestore
+      .fluent()
+      .update()
+      .in_col("boards")
+      .precondition(FirestoreWritePrecondition::Exists(false))
+      .document_id(board_id)
+      .object(&board)
+      .add_to_transaction(&mut transaction)?;
+    add_join_code(firestore, &mut transaction, board_id, &join_code)?;
+    add_writes(&mut transaction)?;
+    match transaction.commit().await {
+      Ok(_) => return get(firestore, board_id).await,
+      Err(FirestoreError::DataConflictError(_)) => continue,
+      Err(e) => return Err(e.into()),
+    }
+  }
+  Err(Error::Other("Couldn't find an unused join code.".into()))
+}
+
+// Copies a board with its columns and cards. Boards can hold more documents than a
+// transaction allows, so the columns and cards are written in batches and the board
+// itself, with its join code, last: until then the copy isn't listed anywhere, and a
+// failure part way removes whatever was written. Copied cards are pointed at the new
+// column and parent documents; cards whose column no longer exists are left behind.
+pub async fn clone(
+  firestore: &FirestoreDb,
+  participant: &Participant,
+  mut board: NewBoard,
+  columns: Vec<Column>,
+  cards: Vec<Card>,
+  keep_votes: bool,
+  retention: Duration,
+) -> Result<Board, Error> {
+  board.expire_at = Some(FirestoreTimestamp(Utc::now() + retention));
+  board.owner = Some(FirestoreReference(format!(
+    "{}/participants/{}",
+    firestore.get_documents_path(),
+    participant.id
+  )));
+  let board_id = generate_document_id();
+  let result = match clone_contents(firestore, &board_id, columns, cards, keep_votes).await {
+    Ok(()) => create(firestore, &board_id, board, |_| Ok(())).await,
+    Err(e) => Err(e),
+  };
+  if result.is_err() {
+    if let Err(e) = delete(firestore, &board_id).await {
+      error!("Failed to remove partial copy {} of a board: {}", board_id, e);
+    }
+  }
+  result
+}
+
+async fn clone_contents(
+  firestore: &FirestoreDb,
+  board_id: &String,
+  columns: Vec<Column>,
+  cards: Vec<Card>,
+  keep_votes: bool,
+) -> Result<(), Error> {
+  let board_path = firestore.parent_path("boards", board_id)?;
+  let column_ids: HashMap<String, String> = columns
+    .iter()
+    .map(|column| (column.id.clone(), generate_document_id()))
+    .collect();
+  let card_ids: HashMap<String, String> = cards
+    .iter()
+    .filter(|card| column_ids.contains_key(&card.column_id()))
+    .map(|card| (card.id.clone(), generate_document_id()))
+    .collect();
+
+  let new_columns: Vec<(String, NewColumn)> = columns
+    .into_iter()
+    .map(|column| (column_ids[&column.id].clone(), column.into()))
+    .collect();
+  let new_cards: Vec<(String, ClonedCard)> = cards
+    .into_iter()
+    .filter_map(|card| {
+      let card_id = card_ids.get(&card.id)?.clone();
+      let column = FirestoreReference(format!(
+        "{}/columns/{}",
+        board_path,
+        column_ids[&card.column_id()]
+      ));
+      let parent = card
+        .parent_id()
+        .and_then(|parent_id| card_ids.get(&parent_id))
+        .map(|parent_id| FirestoreReference(format!("{}/cards/{}", board_path, parent_id)));
+      Some((card_id, ClonedCard::from_card(card, column, parent, keep_votes)))
+    })
+    .collect();
+
+  let writer = firestore.create_simple_batch_writer().await?;
+  for chunk in new_columns.chunks(MAX_BATCH_WRITES) {
+    let mut batch = writer.new_batch();
+    for (column_id, new_column) in chunk {
+      firestore
+        .fluent()
+        .update()
+        .in_col("columns")
+        .precondition(FirestoreWritePrecondition::Exists(false))
+        .document_id(column_id)
+        .parent(&board_path)
+        .object(new_column)
+        .add_to_batch(&mut batch)?;
+    }
+    check_batch(&batch.write().await?)?;
+  }
+  for chunk in new_cards.chunks(MAX_BATCH_WRITES) {
+    let mut batch = writer.new_batch();
+    for (card_id, new_card) in chunk {
+      firestore
+        .fluent()
+        .update()
+        .in_col("cards")
+        .precondition(FirestoreWritePrecondition::Exists(false))
+        .document_id(card_id)
+        .parent(&board_path)
+        .object(new_card)
+        .add_to_batch(&mut batch)?;
+    }
+    check_batch(&batch.write().await?)?;
+  }
+  Ok(())
+}
+
+// Boards are listed newest first. Each round reads one more member document than
+// the page needs, and loads only those boards; boards in the trash or already gone
+// are skipped, so rounds continue until the page fills or the participant's members
+// run out.
+pub async fn list(
+  firestore: &FirestoreDb,
+  participant: &Participant,
+  limit: usize,
+  cursor: Option<&BoardCursor>,
+) -> Result<(Vec<Board>, Option<BoardCursor>), Error> {
+  adopt_legacy_boards(firestore, participant).await?;
+
+  let mut page: Vec<(BoardCursor, Board)> = vec![];
+  let mut after = cursor.cloned();
+  loop {
+    let positions =
+      list_participant_board_positions(firestore, participant, limit + 1, after.as_ref())
+        .await?;
+    let exhausted = positions.len() <= limit;
+    after = positions.last().cloned();
+
+    let mut object_stream: BoxStream<(_, Option<BoardInFirestore>)> = firestore
+      .fluent()
+      .select()
+      .by_id_in("boards")
+      .obj()
+      .batch(positions.iter().map(|p| p.id.clone()))
+      .await?;
+    let mut boards: HashMap<String, Board> = HashMap::new();
+    while let Some((id, board)) = object_stream.next().await {
+      if let Some(board) = board.filter(|board| board.deleted_at.is_none()) {
+        boards.insert(id, board.into());
+      }
+    }
+    page.extend(
+      positions
+        .into_iter()
+        .filter_map(|p| boards.remove(&p.id).map(|board| (p, board))),
+    );
+    if page.len() > limit || exhausted {
+      break;
+    }
+  }
+
+  let next_cursor = match page.len() > limit {
+    true => {
+      page.truncate(limit);
+      page.last().map(|(position, _)| position.clone())
+    }
+    false => None,
+  };
+  Ok((page.into_iter().map(|(_, board)| board).collect(), next_cursor))
+}
+
+// Boards in the trash are treated as missing everywhere but the restore endpoint.
+pub async fn get(firestore: &FirestoreDb, board_id: &String) -> Result<Board, Error> {
+  match get_including_deleted(firestore, board_id).await? {
+    Board {
+      deleted_at: Some(_),
+      ..
+    } => Err(Error::NotFound),
+    board => Ok(board),
+  }
+}
+
+pub async fn get_including_deleted(
+  firestore: &FirestoreDb,
+  board_id: &String,
+) -> Result<Board, Error> {
+  firestore
+    .fluent()
+    .select()
+    .by_id_in("boards")
+    .obj::<BoardInFirestore>()
+    .one(board_id)
+    .await?
+    .ok_or(Error::NotFound)
+    .map(|board| board.into())
+}
+
+async fn set_deleted_at(
+  firestore: &FirestoreDb,
+  board_id: &String,
+  deleted_at: Option<FirestoreTimestamp>,
+) -> Result<Board, Error> {
+  firestore
+    .fluent()
+    .update()
+    .fields(paths!(BoardDeletion::deleted_at))
+    .in_col("boards")
+    .document_id(board_id)
+    .object(&BoardDeletion { deleted_at })
+    .execute::<BoardInFirestore>()
+    .await
+    .map(|board| board.into())
+    .map_err(|e| e.into())
+}
+
+pub async fn move_to_trash(firestore: &FirestoreDb, board_id: &String) -> Result<(), Error> {
+  set_deleted_at(firestore, board_id, Some(FirestoreTimestamp(Utc::now()))).await?;
+  Ok(())
+}
+
+pub async fn restore(firestore: &FirestoreDb, board_id: &String) -> Result<Board, Error> {
+  set_deleted_at(firestore, board_id, None).await
+}
+
+// Pushes the board's expiry back to a full retention period from now.
+pub async fn extend(
+  firestore: &FirestoreDb,
+  board_id: &String,
+  retention: Duration,
+) -> Result<Board, Error> {
   firestore
     .fluent()
-    .insert()
-    .into("boards")
-    .generate_document_id()
-    .object(&new_board)
+    .update()
+    .fields(paths!(BoardExpiry::expire_at))
+    .in_col("boards")
+    .document_id(board_id)
+    .object(&BoardExpiry {
+      expire_at: FirestoreTimestamp(Utc::now() + retention),
+    })
     .execute::<BoardInFirestore>()
     .await
     .map(|board| board.into())
     .map_err(|e| e.into())
 }
 
-pub async fn list(firestore: &FirestoreDb, participant: &Participant) -> Result<Vec<Board>, Error> {
-  let boards = get_participant_board_ids(firestore, participant).await?;
-  let mut object_stream: BoxStream<(_, Option<BoardInFirestore>)> = firestore
+// Hard deletes every board whose expiry has passed, trashed or not.
+pub async fn purge_expired(firestore: &FirestoreDb) -> Result<usize, Error> {
+  let now = FirestoreTimestamp(Utc::now());
+  let board_ids: Vec<String> = firestore
     .fluent()
     .select()
-    .by_id_in("boards")
-    .obj()
-    .batch(boards)
-    .await?;
+    .fields(paths!(BoardInFirestore::expire_at))
+    .from("boards")
+    .filter(|q| q.for_all([q.field(path!(BoardInFirestore::expire_at)).less_than(&now)]))
+    .obj::<BoardCreatedAtInFirestore>()
+    .query()
+    .await?
+    .into_iter()
+    .map(|board| board._firestore_id)
+    .collect();
+  Ok(delete_each(firestore, &board_ids).await)
+}
 
-  let mut boards: Vec<Board> = vec![];
-  while let Some((_, Some(board))) = object_stream.next().await {
-    boards.push(board.into());
+// Hard deletes every board that has been in the trash for longer than the window.
+pub async fn purge_deleted(firestore: &FirestoreDb, window: Duration) -> Result<usize, Error> {
+  let cutoff = FirestoreTimestamp(Utc::now() - window);
+  let board_ids: Vec<String> = firestore
+    .fluent()
+    .select()
+    .fields(paths!(BoardInFirestore::deleted_at))
+    .from("boards")
+    .filter(|q| q.for_all([q.field(path!(BoardInFirestore::deleted_at)).less_than(&cutoff)]))
+    .obj::<BoardCreatedAtInFirestore>()
+    .query()
+    .await?
+    .into_iter()
+    .map(|board| board._firestore_id)
+    .collect();
+  Ok(delete_each(firestore, &board_ids).await)
+}
+
+// One board failing to delete shouldn't hold up the rest, so failures are logged
+// and left for the next purge. Returns how many boards were deleted.
+async fn delete_each(firestore: &FirestoreDb, board_ids: &[String]) -> usize {
+  let mut deleted = 0;
+  for board_id in board_ids {
+    match delete(firestore, board_id).await {
+      Ok(()) => deleted += 1,
+      Err(e) => error!("Failed to purge board {}: {}", board_id, e),
+    }
   }
-  Ok(boards)
+  deleted
 }
 
-pub async fn get(firestore: &FirestoreDb, board_id: &String) -> Result<Board, Error> {
+// Role changes need a member document, which participants that haven't been
+// migrated yet don't have.
+async fn adopt_legacy_members(
+  firestore: &FirestoreDb,
+  participants: &[&FirestoreReference],
+) -> Result<(), Error> {
+  for participant in participants {
+    let participant = Participant {
+      id: participant.0.split('/').next_back().unwrap().to_string(),
+    };
+    adopt_legacy_boards(firestore, &participant).await?;
+  }
+  Ok(())
+}
+
+pub async fn add_facilitator(
+  firestore: &FirestoreDb,
+  board_id: &String,
+  facilitator: &FirestoreReference,
+) -> Result<Board, Error> {
+  adopt_legacy_members(firestore, &[facilitator]).await?;
+  let mut transaction = firestore.begin_transaction().await?;
+  firestore
+    .fluent()
+    .update()
+    .in_col("boards")
+    .precondition(FirestoreWritePrecondition::Exists(true))
+    .document_id(board_id)
+    .transforms(|t| {
+      t.fields([t
+        .field(path!(BoardInFirestore::facilitators))
+        .append_missing_elements([facilitator.clone()])])
+    })
+    .only_transform()
+    .add_to_transaction(&mut transaction)?;
+  set_member_role(
+    firestore,
+    &mut transaction,
+    board_id,
+    facilitator,
+    BoardRole::Facilitator,
+  )?;
+  transaction.commit().await?;
+  get(firestore, board_id).await
+}
+
+pub async fn remove_facilitator(
+  firestore: &FirestoreDb,
+  board_id: &String,
+  facilitator: &FirestoreReference,
+) -> Result<Board, Error> {
+  adopt_legacy_members(firestore, &[facilitator]).await?;
+  let mut transaction = firestore.begin_transaction().await?;
+  firestore
+    .fluent()
+    .update()
+    .in_col("boards")
+    .precondition(FirestoreWritePrecondition::Exists(true))
+    .document_id(board_id)
+    .transforms(|t| {
+      t.fields([t
+        .field(path!(BoardInFirestore::facilitators))
+        .remove_all_from_array([facilitator.clone()])])
+    })
+    .only_transform()
+    .add_to_transaction(&mut transaction)?;
+  set_member_role(
+    firestore,
+    &mut transaction,
+    board_id,
+    facilitator,
+    BoardRole::Participant,
+  )?;
+  transaction.commit().await?;
+  get(firestore, board_id).await
+}
+
+// Hands the board to a new owner, who stops being a facilitator if they were one,
+// and records the change on the board.
+pub async fn transfer_ownership(
+  firestore: &FirestoreDb,
+  board_id: &String,
+  from: &FirestoreReference,
+  to: &FirestoreReference,
+) -> Result<Board, Error> {
+  let transfer = OwnershipTransferInFirestore {
+    from: from.clone(),
+    to: to.clone(),
+    transferred_at: FirestoreTimestamp(Utc::now()),
+  };
+  adopt_legacy_members(firestore, &[from, to]).await?;
+  // Transforms are only applied to updates written through a transaction.
+  let mut transaction = firestore.begin_transaction().await?;
+  firestore
+    .fluent()
+    .update()
+    .fields(paths!(BoardOwnership::owner))
+    .in_col("boards")
+    .precondition(FirestoreWritePrecondition::Exists(true))
+    .document_id(board_id)
+    .object(&BoardOwnership { owner: to.clone() })
+    .transforms(|t| {
+      t.fields([
+        t.field(path!(BoardInFirestore::facilitators))
+          .remove_all_from_array([to.clone()]),
+        t.field(path!(BoardInFirestore::ownership_transfers))
+          .append_missing_elements([transfer.clone()]),
+      ])
+    })
+    .add_to_transaction(&mut transaction)?;
+  set_member_role(firestore, &mut transaction, board_id, from, BoardRole::Participant)?;
+  set_member_role(firestore, &mut transaction, board_id, to, BoardRole::Owner)?;
+  transaction.commit().await?;
+  get(firestore, board_id).await
+}
+
+// Claims an unused join code for the board, releasing the one it had before. The
+// code's index document is created with a precondition, so a code that's already
+// taken fails the commit and another is drawn.
+pub async fn assign_join_code(
+  firestore: &FirestoreDb,
+  board_id: &String,
+  previous: Option<&String>,
+) -> Result<Board, Error> {
+  for _ in 0..JOIN_CODE_ATTEMPTS {
+    let join_code = generate_join_code();
+    let mut transaction = firestore.begin_transaction().await?;
+    if let Some(previous) = previous {
+      firestore
+        .fluent()
+        .delete()
+        .from("join_codes")
+        .document_id(previous)
+        .add_to_transaction(&mut transaction)?;
+    }
+    add_join_code(firestore, &mut transaction, board_id, &join_code)?;
+    firestore
+      .fluent()
+      .update()
+      .fields(paths!(BoardJoinCode::join_code))
+      .in_col("boards")
+      .precondition(FirestoreWritePrecondition::Exists(true))
+      .document_id(board_id)
+      .object(&BoardJoinCode {
+        join_code: Some(join_code),
+      })
+      .add_to_transaction(&mut transaction)?;
+    match transaction.commit().await {
+      Ok(_) => return get(firestore, board_id).await,
+      Err(FirestoreError::DataConflictError(_)) => continue,
+      Err(e) => return Err(e.into()),
+    }
+  }
+  Err(Error::Other("Couldn't find an unused join code.".into()))
+}
+
+// Indexes the code under the board. The precondition fails the commit if the code
+// is already taken.
+fn add_join_code(
+  firestore: &FirestoreDb,
+  transaction: &mut FirestoreTransaction<'_>,
+  board_id: &String,
+  join_code: &String,
+) -> Result<(), Error> {
+  firestore
+    .fluent()
+    .update()
+    .in_col("join_codes")
+    .precondition(FirestoreWritePrecondition::Exists(false))
+    .document_id(join_code)
+    .object(&JoinCodeInFirestore {
+      board: FirestoreReference(format!(
+        "{}/boards/{}",
+        firestore.get_documents_path(),
+        board_id
+      )),
+    })
+    .add_to_transaction(transaction)?;
+  Ok(())
+}
+
+pub async fn disable_join_code(
+  firestore: &FirestoreDb,
+  board_id: &String,
+  join_code: &String,
+) -> Result<Board, Error> {
+  let mut transaction = firestore.begin_transaction().await?;
   firestore
+    .fluent()
+    .delete()
+    .from("join_codes")
+    .document_id(join_code)
+    .add_to_transaction(&mut transaction)?;
+  firestore
+    .fluent()
+    .update()
+    .fields(paths!(BoardJoinCode::join_code))
+    .in_col("boards")
+    .precondition(FirestoreWritePrecondition::Exists(true))
+    .document_id(board_id)
+    .object(&BoardJoinCode { join_code: None })
+    .add_to_transaction(&mut transaction)?;
+  transaction.commit().await?;
+  get(firestore, board_id).await
+}
+
+// Returns the ID of the board the code was issued to.
+pub async fn resolve_join_code(firestore: &FirestoreDb, join_code: &str) -> Result<String, Error> {
+  let join_code: JoinCodeInFirestore = firestore
     .fluent()
     .select()
-    .by_id_in("boards")
-    .obj::<BoardInFirestore>()
-    .one(board_id)
+    .by_id_in("join_codes")
+    .obj()
+    .one(normalise_join_code(join_code))
     .await?
-    .ok_or(Error::NotFound)
-    .map(|board| board.into())
+    .ok_or(Error::NotFound)?;
+  Ok(join_code.board.0.split('/').next_back().unwrap().to_string())
+}
+
+async fn delete_join_codes(firestore: &FirestoreDb, board_id: &String) -> Result<(), Error> {
+  let board_reference = FirestoreReference(format!(
+    "{}/boards/{}",
+    firestore.get_documents_path(),
+    board_id
+  ));
+  let join_codes = firestore
+    .fluent()
+    .select()
+    .from("join_codes")
+    .filter(|q| {
+      q.for_all([q
+        .field(path!(JoinCodeInFirestore::board))
+        .eq(&board_reference)])
+    })
+    .query()
+    .await?;
+  for join_code in join_codes {
+    firestore
+      .fluent()
+      .delete()
+      .from("join_codes")
+      .document_id(join_code.name.split('/').next_back().unwrap())
+      .execute()
+      .await?;
+  }
+  Ok(())
 }
 
 pub async fn update(
@@ -71,7 +692,19 @@ pub async fn update(
     .fluent()
     .update()
     .fields(
-      paths!(BoardMessage::{name, cards_open, voting_open, ice_breaking, data, open_permission})
+      paths!(BoardMessage::{
+        name,
+        cards_open,
+        voting_open,
+        ice_breaking,
+        data,
+        open_permission,
+        max_votes_per_participant,
+        multi_vote,
+        blind_voting,
+        anonymous,
+        private
+      })
         .into_iter()
         .filter(|f| serialised_board.get(f).is_some()),
     )
@@ -84,7 +717,55 @@ pub async fn update(
     .map_err(|e| e.into())
 }
 
+// Firestore accepts at most 500 writes in a single batch.
+pub const MAX_BATCH_WRITES: usize = 500;
+
+// Every collection nested under a board document.
+pub const BOARD_SUBCOLLECTIONS: [&str; 4] = ["cards", "columns", "action_items", "members"];
+
+async fn delete_subcollection(
+  firestore: &FirestoreDb,
+  board_id: &String,
+  collection: &str,
+) -> Result<(), Error> {
+  let parent = firestore.parent_path("boards", board_id)?;
+  let document_ids: Vec<String> = firestore
+    .fluent()
+    .list()
+    .from(collection)
+    .parent(&parent)
+    .stream_all()
+    .await?
+    .map(|document| document.name.split('/').next_back().unwrap().to_string())
+    .collect()
+    .await;
+
+  let writer = firestore.create_simple_batch_writer().await?;
+  for chunk in document_ids.chunks(MAX_BATCH_WRITES) {
+    let mut batch = writer.new_batch();
+    for document_id in chunk {
+      firestore
+        .fluent()
+        .delete()
+        .from(collection)
+        .document_id(document_id)
+        .parent(&parent)
+        .add_to_batch(&mut batch)?;
+    }
+    check_batch(&batch.write().await?)?;
+  }
+  Ok(())
+}
+
+// Removes the board's subcollections, its members included, its place in legacy
+// `boards` arrays and its join code before the board itself, so a failure part way
+// leaves a board that can be deleted again rather than orphaned documents.
 pub async fn delete(firestore: &FirestoreDb, board_id: &String) -> Result<(), Error> {
+  for collection in BOARD_SUBCOLLECTIONS {
+    delete_subcollection(firestore, board_id, collection).await?;
+  }
+  remove_board_from_participants(firestore, board_id).await?;
+  delete_join_codes(firestore, board_id).await?;
   firestore
     .fluent()
     .delete()
@@ -99,6 +780,7 @@ pub async fn delete(firestore: &FirestoreDb, board_id: &String) -> Result<(), Er
 mod tests {
   use super::*;
   use crate::boards::models::BoardMessage;
+  use crate::participants::db::get_participant_board_ids;
   use crate::participants::models::Participant;
 
   // Run with: FIRESTORE_EMULATOR_HOST=localhost:8080 cargo test -- --ignored
@@ -128,6 +810,10 @@ mod tests {
     Participant { id: "integration-test-participant".to_string() }
   }
 
+  fn retention() -> Duration {
+    Duration::days(180)
+  }
+
   fn board_msg(name: &str) -> BoardMessage {
     BoardMessage {
       name: Some(name.to_string()),
@@ -136,6 +822,7 @@ mod tests {
       ice_breaking: None,
       data: None,
       open_permission: None,
+      ..Default::default()
     }
   }
 
@@ -144,7 +831,8 @@ mod tests {
   async fn new_board_can_be_retrieved_by_id() {
     let db = emulator_db().await;
     let participant = test_participant();
-    let board = new(&db, &participant, board_msg("Integration Test Board")).await.unwrap();
+    let board =
+      new(&db, &participant, board_msg("Integration Test Board"), retention()).await.unwrap();
     let fetched = get(&db, &board.id).await.unwrap();
     assert_eq!(fetched.id, board.id);
     assert_eq!(fetched.name, "Integration Test Board");
@@ -153,6 +841,85 @@ mod tests {
     delete(&db, &board.id).await.unwrap();
   }
 
+  #[test]
+  fn generated_document_ids_look_like_firestore_ids() {
+    let id = generate_document_id();
+    assert_eq!(id.len(), 20);
+    assert!(id.chars().all(|c| c.is_ascii_alphanumeric()));
+    assert_ne!(id, generate_document_id());
+  }
+
+  #[test]
+  fn generated_join_codes_are_a_word_and_six_digits() {
+    let join_code = generate_join_code();
+    let (word, digits) = join_code.split_once('-').unwrap();
+    assert!(JOIN_CODE_WORDS.contains(&word));
+    assert_eq!(digits.len(), 6);
+    assert!(digits.chars().all(|c| c.is_ascii_digit()));
+  }
+
+  #[test]
+  fn join_codes_are_normalised_for_lookup() {
+    assert_eq!(normalise_join_code(" fox-4821\n"), "FOX-4821");
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn new_board_gets_a_join_code_that_resolves_to_it() {
+    let db = emulator_db().await;
+    let participant = test_participant();
+    let board = new(&db, &participant, board_msg("Join Code"), retention()).await.unwrap();
+    let join_code = board.join_code.clone().unwrap();
+    assert_eq!(
+      resolve_join_code(&db, &join_code.to_lowercase()).await.unwrap(),
+      board.id
+    );
+    delete(&db, &board.id).await.unwrap();
+    assert!(matches!(resolve_join_code(&db, &join_code).await, Err(Error::NotFound)));
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn regenerating_a_join_code_releases_the_old_one() {
+    let db = emulator_db().await;
+    let participant = test_participant();
+    let board = new(&db, &participant, board_msg("Regenerate"), retention()).await.unwrap();
+    let old_code = board.join_code.clone().unwrap();
+    let board = assign_join_code(&db, &board.id, Some(&old_code)).await.unwrap();
+    let new_code = board.join_code.clone().unwrap();
+    assert_ne!(new_code, old_code);
+    assert!(matches!(resolve_join_code(&db, &old_code).await, Err(Error::NotFound)));
+    assert_eq!(resolve_join_code(&db, &new_code).await.unwrap(), board.id);
+
+    let board = disable_join_code(&db, &board.id, &new_code).await.unwrap();
+    assert!(board.join_code.is_none());
+    assert!(matches!(resolve_join_code(&db, &new_code).await, Err(Error::NotFound)));
+    delete(&db, &board.id).await.unwrap();
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn new_with_columns_creates_board_and_columns() {
+    use crate::columns::get_columns;
+    let db = emulator_db().await;
+    let participant = test_participant();
+    let columns = vec![
+      ColumnMessage { name: Some("Start".into()), data: None, position: Some(0) },
+      ColumnMessage { name: Some("Stop".into()), data: None, position: Some(1) },
+    ];
+    let board = new_with_columns(&db, &participant, board_msg("Templated"), columns, retention())
+      .await
+      .unwrap();
+    assert_eq!(board.name, "Templated");
+    let mut names: Vec<String> =
+      get_columns(&db, &board.id).await.unwrap().into_values().map(|c| c.name).collect();
+    names.sort();
+    assert_eq!(names, vec!["Start", "Stop"]);
+    let join_code = board.join_code.clone().unwrap();
+    assert_eq!(resolve_join_code(&db, &join_code).await.unwrap(), board.id);
+    delete(&db, &board.id).await.unwrap();
+  }
+
   #[tokio::test]
   #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
   async fn get_nonexistent_board_returns_not_found() {
@@ -166,7 +933,7 @@ mod tests {
   async fn update_board_changes_fields() {
     let db = emulator_db().await;
     let participant = test_participant();
-    let board = new(&db, &participant, board_msg("Before Update")).await.unwrap();
+    let board = new(&db, &participant, board_msg("Before Update"), retention()).await.unwrap();
     let updated = update(
       &db,
       &board.id,
@@ -177,6 +944,7 @@ mod tests {
         ice_breaking: None,
         data: None,
         open_permission: None,
+        ..Default::default()
       },
     )
     .await
@@ -201,7 +969,9 @@ mod tests {
         ice_breaking: None,
         data: None,
         open_permission: Some(true),
+        ..Default::default()
       },
+      retention(),
     )
     .await
     .unwrap();
@@ -216,7 +986,8 @@ mod tests {
   async fn update_board_open_permission_persists() {
     let db = emulator_db().await;
     let participant = test_participant();
-    let board = new(&db, &participant, board_msg("Toggle Anyone Is Owner")).await.unwrap();
+    let board =
+      new(&db, &participant, board_msg("Toggle Anyone Is Owner"), retention()).await.unwrap();
     assert!(!board.open_permission);
     let updated = update(
       &db,
@@ -228,6 +999,7 @@ mod tests {
         ice_breaking: None,
         data: None,
         open_permission: Some(true),
+        ..Default::default()
       },
     )
     .await
@@ -253,7 +1025,9 @@ mod tests {
         ice_breaking: None,
         data: None,
         open_permission: Some(true),
+        ..Default::default()
       },
+      retention(),
     )
     .await
     .unwrap();
@@ -268,6 +1042,7 @@ mod tests {
         ice_breaking: None,
         data: None,
         open_permission: Some(false),
+        ..Default::default()
       },
     )
     .await
@@ -278,14 +1053,388 @@ mod tests {
     delete(&db, &board.id).await.unwrap();
   }
 
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn list_pages_newest_first_until_exhausted() {
+    use crate::participants::db::add_participant_board;
+    let db = emulator_db().await;
+    let participant = crate::participants::db::new(&db).await.unwrap();
+    let mut created = vec![];
+    for name in ["First", "Second", "Third"] {
+      let board = new(&db, &participant, board_msg(name), retention()).await.unwrap();
+      add_participant_board(&db, &participant, &board).await.unwrap();
+      created.push(board.id);
+    }
+
+    let (first_page, cursor) = list(&db, &participant, 2, None).await.unwrap();
+    assert_eq!(
+      first_page.iter().map(|b| b.name.as_str()).collect::<Vec<_>>(),
+      vec!["Third", "Second"]
+    );
+    let cursor = cursor.expect("a full page should have a next cursor");
+
+    let (second_page, cursor) = list(&db, &participant, 2, Some(&cursor)).await.unwrap();
+    assert_eq!(
+      second_page.iter().map(|b| b.name.as_str()).collect::<Vec<_>>(),
+      vec!["First"]
+    );
+    assert!(cursor.is_none());
+
+    for board_id in created {
+      delete(&db, &board_id).await.unwrap();
+    }
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn list_orders_by_board_creation_not_by_joining() {
+    use crate::participants::db::add_participant_board;
+    let db = emulator_db().await;
+    let participant = crate::participants::db::new(&db).await.unwrap();
+    let older = new(&db, &test_participant(), board_msg("Older"), retention()).await.unwrap();
+    let newer = new(&db, &test_participant(), board_msg("Newer"), retention()).await.unwrap();
+    add_participant_board(&db, &participant, &newer).await.unwrap();
+    add_participant_board(&db, &participant, &older).await.unwrap();
+
+    let (page, _) = list(&db, &participant, 10, None).await.unwrap();
+    assert_eq!(page.iter().map(|b| b.name.as_str()).collect::<Vec<_>>(), vec!["Newer", "Older"]);
+
+    delete(&db, &older.id).await.unwrap();
+    delete(&db, &newer.id).await.unwrap();
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn list_fills_the_page_past_boards_in_the_trash() {
+    use crate::participants::db::add_participant_board;
+    let db = emulator_db().await;
+    let participant = crate::participants::db::new(&db).await.unwrap();
+    let mut created = vec![];
+    for name in ["First", "Second", "Third", "Fourth"] {
+      let board = new(&db, &participant, board_msg(name), retention()).await.unwrap();
+      add_participant_board(&db, &participant, &board).await.unwrap();
+      created.push(board.id);
+    }
+    move_to_trash(&db, &created[3]).await.unwrap();
+    move_to_trash(&db, &created[2]).await.unwrap();
+
+    let (page, cursor) = list(&db, &participant, 1, None).await.unwrap();
+    assert_eq!(page.iter().map(|b| b.name.as_str()).collect::<Vec<_>>(), vec!["Second"]);
+    let (page, cursor) = list(&db, &participant, 1, cursor.as_ref()).await.unwrap();
+    assert_eq!(page.iter().map(|b| b.name.as_str()).collect::<Vec<_>>(), vec!["First"]);
+    assert!(cursor.is_none());
+
+    for board_id in created {
+      delete(&db, &board_id).await.unwrap();
+    }
+  }
+
   #[tokio::test]
   #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
   async fn delete_board_makes_it_unretrievable() {
     let db = emulator_db().await;
     let participant = test_participant();
-    let board = new(&db, &participant, board_msg("To Be Deleted")).await.unwrap();
+    let board = new(&db, &participant, board_msg("To Be Deleted"), retention()).await.unwrap();
     delete(&db, &board.id).await.unwrap();
     let result = get(&db, &board.id).await;
     assert!(matches!(result, Err(crate::error::Error::NotFound)));
   }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn delete_board_removes_subcollections_and_participant_references() {
+    use crate::action_items;
+    use crate::action_items::models::ActionItemMessage;
+    use crate::cards;
+    use crate::cards::models::CardMessage;
+    use crate::columns::get_columns;
+    use crate::participants::db::add_participant_board;
+    let db = emulator_db().await;
+    let participant = Participant { id: "cascade-delete-test-participant".to_string() };
+    let columns = vec![ColumnMessage { name: Some("Start".into()), data: None, position: Some(0) }];
+    let board = new_with_columns(&db, &participant, board_msg("Cascade"), columns, retention())
+      .await
+      .unwrap();
+    add_participant_board(&db, &participant, &board).await.unwrap();
+    let column_id = get_columns(&db, &board.id).await.unwrap().into_keys().next().unwrap();
+    cards::db::new(&db, &participant, &board.id, CardMessage {
+      author: None,
+      text: Some("A card".into()),
+      column: Some(format!("{}/boards/{}/columns/{}", db.get_documents_path(), board.id, column_id)),
+    })
+    .await
+    .unwrap();
+    action_items::db::new(&db, &participant, &board.id, ActionItemMessage {
+      text: Some("An action".into()),
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    delete(&db, &board.id).await.unwrap();
+    assert!(get_columns(&db, &board.id).await.unwrap().is_empty());
+    assert!(cards::db::list(&db, &board.id).await.unwrap().is_empty());
+    assert!(action_items::db::list(&db, &board.id).await.unwrap().is_empty());
+    assert!(!get_participant_board_ids(&db, &participant).await.unwrap().contains(&board.id));
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn clone_copies_more_cards_than_a_transaction_allows() {
+    use crate::cards;
+    let db = emulator_db().await;
+    let participant = test_participant();
+    let column = Column {
+      id: "col1".into(),
+      name: "Start".into(),
+      created_at: 0,
+      data: serde_json::Value::Null,
+      position: 0,
+    };
+    let owner = FirestoreReference(format!("{}/participants/p1", db.get_documents_path()));
+    let source_column =
+      FirestoreReference(format!("{}/boards/src/columns/col1", db.get_documents_path()));
+    let cards = (0..MAX_BATCH_WRITES + 10)
+      .map(|i| Card {
+        id: format!("card{i}"),
+        column: source_column.clone(),
+        owner: owner.clone(),
+        author: "".into(),
+        text: format!("Card {i}"),
+        created_at: 0,
+        votes: vec![],
+        vote_counts: HashMap::new(),
+        reactions: HashMap::new(),
+        parent: None,
+      })
+      .collect();
+    let copy: NewBoard = board_msg("Big").into();
+    let board = clone(&db, &participant, copy, vec![column], cards, false, retention())
+      .await
+      .unwrap();
+    assert_eq!(get(&db, &board.id).await.unwrap().name, "Big");
+    assert_eq!(cards::db::list(&db, &board.id).await.unwrap().len(), MAX_BATCH_WRITES + 10);
+    let join_code = board.join_code.clone().unwrap();
+    assert_eq!(resolve_join_code(&db, &join_code).await.unwrap(), board.id);
+    delete(&db, &board.id).await.unwrap();
+  }
+
+  // Joins the board as the given participant, returning their reference.
+  async fn join(db: &FirestoreDb, board: &Board, participant_id: &str) -> FirestoreReference {
+    use crate::participants::db::add_participant_board;
+    let participant = Participant { id: participant_id.to_string() };
+    add_participant_board(db, &participant, board).await.unwrap();
+    FirestoreReference(format!("{}/participants/{}", db.get_documents_path(), participant_id))
+  }
+
+  async fn member(
+    db: &FirestoreDb,
+    board_id: &String,
+    participant_id: &str,
+  ) -> Option<crate::participants::models::MemberInFirestore> {
+    db.fluent()
+      .select()
+      .by_id_in("members")
+      .parent(db.parent_path("boards", board_id).unwrap())
+      .obj()
+      .one(participant_id)
+      .await
+      .unwrap()
+  }
+
+  async fn member_role(db: &FirestoreDb, board_id: &String, participant_id: &str) -> BoardRole {
+    member(db, board_id, participant_id).await.unwrap().role
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn touching_a_member_refreshes_last_seen_without_creating_members() {
+    use crate::participants::db::{add_participant_board, touch_member};
+    let db = emulator_db().await;
+    let participant = crate::participants::db::new(&db).await.unwrap();
+    let board = new(&db, &participant, board_msg("Touched"), retention()).await.unwrap();
+    let stranger = Participant { id: "never-joined-touched-board".to_string() };
+
+    add_participant_board(&db, &participant, &board).await.unwrap();
+    let before = member(&db, &board.id, &participant.id).await.unwrap();
+    touch_member(&db, &board.id, &participant).await.unwrap();
+    let after = member(&db, &board.id, &participant.id).await.unwrap();
+    assert!(after.last_seen.0 > before.last_seen.0);
+    assert_eq!(after.joined_at.0, before.joined_at.0);
+
+    touch_member(&db, &board.id, &stranger).await.unwrap();
+    assert!(member(&db, &board.id, &stranger.id).await.is_none());
+    delete(&db, &board.id).await.unwrap();
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn facilitators_can_be_added_and_removed() {
+    let db = emulator_db().await;
+    let board = new(&db, &test_participant(), board_msg("Facilitated"), retention()).await.unwrap();
+    assert!(board.facilitators.is_empty());
+    let facilitator = join(&db, &board, "facilitator").await;
+    assert_eq!(member_role(&db, &board.id, "facilitator").await, BoardRole::Participant);
+    add_facilitator(&db, &board.id, &facilitator).await.unwrap();
+    let board = add_facilitator(&db, &board.id, &facilitator).await.unwrap();
+    assert_eq!(board.facilitators, vec![facilitator.clone()]);
+    assert_eq!(member_role(&db, &board.id, "facilitator").await, BoardRole::Facilitator);
+    let board = remove_facilitator(&db, &board.id, &facilitator).await.unwrap();
+    assert!(board.facilitators.is_empty());
+    assert_eq!(member_role(&db, &board.id, "facilitator").await, BoardRole::Participant);
+    delete(&db, &board.id).await.unwrap();
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn facilitator_must_be_a_member() {
+    let db = emulator_db().await;
+    let board = new(&db, &test_participant(), board_msg("Strangers"), retention()).await.unwrap();
+    let stranger = FirestoreReference(format!("{}/participants/stranger", db.get_documents_path()));
+    assert!(add_facilitator(&db, &board.id, &stranger).await.is_err());
+    assert!(get(&db, &board.id).await.unwrap().facilitators.is_empty());
+    delete(&db, &board.id).await.unwrap();
+  }
+
+  // Lists the board in the participant's legacy `boards` array, the way membership
+  // was recorded before member documents.
+  async fn join_legacy(db: &FirestoreDb, board_id: &String, participant_id: &str) -> Participant {
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct LegacyParticipant {
+      boards: Vec<FirestoreReference>,
+    }
+
+    let boards =
+      vec![FirestoreReference(format!("{}/boards/{}", db.get_documents_path(), board_id))];
+    db.fluent()
+      .update()
+      .in_col("participants")
+      .document_id(participant_id)
+      .object(&LegacyParticipant { boards })
+      .execute::<LegacyParticipant>()
+      .await
+      .unwrap();
+    Participant { id: participant_id.to_string() }
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn unmigrated_participants_keep_their_boards_and_can_facilitate() {
+    use crate::participants::db::{is_board_member, legacy_board_ids};
+    let db = emulator_db().await;
+    let board = new(&db, &test_participant(), board_msg("Legacy"), retention()).await.unwrap();
+    let legacy = join_legacy(&db, &board.id, "unmigrated-legacy-participant").await;
+
+    assert!(is_board_member(&db, &legacy, &board.id).await.unwrap());
+    assert!(get_participant_board_ids(&db, &legacy).await.unwrap().contains(&board.id));
+    let (listed, _) = list(&db, &legacy, 10, None).await.unwrap();
+    assert_eq!(listed.iter().map(|b| &b.id).collect::<Vec<_>>(), vec![&board.id]);
+    assert!(legacy_board_ids(&db, &legacy).await.unwrap().is_empty());
+    assert!(is_board_member(&db, &legacy, &board.id).await.unwrap());
+    let reference =
+      FirestoreReference(format!("{}/participants/{}", db.get_documents_path(), legacy.id));
+    add_facilitator(&db, &board.id, &reference).await.unwrap();
+    assert_eq!(member_role(&db, &board.id, &legacy.id).await, BoardRole::Facilitator);
+    delete(&db, &board.id).await.unwrap();
+    db.fluent().delete().from("participants").document_id(&legacy.id).execute().await.unwrap();
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn delete_board_removes_it_from_legacy_boards_arrays() {
+    let db = emulator_db().await;
+    let board = new(&db, &test_participant(), board_msg("Legacy gone"), retention()).await.unwrap();
+    let legacy = join_legacy(&db, &board.id, "legacy-participant-of-deleted-board").await;
+    assert!(get_participant_board_ids(&db, &legacy).await.unwrap().contains(&board.id));
+    delete(&db, &board.id).await.unwrap();
+    assert!(!get_participant_board_ids(&db, &legacy).await.unwrap().contains(&board.id));
+    db.fluent().delete().from("participants").document_id(&legacy.id).execute().await.unwrap();
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn transfer_ownership_records_the_change() {
+    let db = emulator_db().await;
+    let board = new(&db, &test_participant(), board_msg("Handed over"), retention()).await.unwrap();
+    join(&db, &board, &test_participant().id).await;
+    let new_owner = join(&db, &board, "new-owner").await;
+    add_facilitator(&db, &board.id, &new_owner).await.unwrap();
+    let transferred =
+      transfer_ownership(&db, &board.id, &board.owner, &new_owner).await.unwrap();
+    assert_eq!(transferred.owner, new_owner);
+    assert_eq!(member_role(&db, &board.id, "new-owner").await, BoardRole::Owner);
+    assert_eq!(
+      member_role(&db, &board.id, &test_participant().id).await,
+      BoardRole::Participant
+    );
+    assert!(transferred.facilitators.is_empty());
+    assert_eq!(transferred.ownership_transfers.len(), 1);
+    assert_eq!(transferred.ownership_transfers[0].from, board.owner);
+    assert_eq!(transferred.ownership_transfers[0].to, new_owner);
+    assert!(transferred.ownership_transfers[0].transferred_at >= board.created_at);
+    delete(&db, &board.id).await.unwrap();
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn trashed_board_is_hidden_and_can_be_restored() {
+    let db = emulator_db().await;
+    let board = new(&db, &test_participant(), board_msg("Trashed"), retention()).await.unwrap();
+    move_to_trash(&db, &board.id).await.unwrap();
+    assert!(matches!(get(&db, &board.id).await, Err(Error::NotFound)));
+    assert!(get_including_deleted(&db, &board.id).await.unwrap().deleted_at.is_some());
+    let restored = restore(&db, &board.id).await.unwrap();
+    assert!(restored.deleted_at.is_none());
+    assert!(get(&db, &board.id).await.is_ok());
+    delete(&db, &board.id).await.unwrap();
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn purge_deleted_removes_boards_past_the_window() {
+    let db = emulator_db().await;
+    let trashed = new(&db, &test_participant(), board_msg("Trashed"), retention()).await.unwrap();
+    let kept = new(&db, &test_participant(), board_msg("Kept"), retention()).await.unwrap();
+    // Trashed long ago, so boards other tests have just trashed aren't purged too.
+    let deleted_at = FirestoreTimestamp(Utc::now() - Duration::days(400));
+    set_deleted_at(&db, &trashed.id, Some(deleted_at)).await.unwrap();
+    purge_deleted(&db, Duration::days(365)).await.unwrap();
+    assert!(matches!(
+      get_including_deleted(&db, &trashed.id).await,
+      Err(Error::NotFound)
+    ));
+    assert!(get(&db, &kept.id).await.is_ok());
+    delete(&db, &kept.id).await.unwrap();
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn new_board_expires_after_retention_and_can_be_extended() {
+    let db = emulator_db().await;
+    let board = new(&db, &test_participant(), board_msg("Expiring"), Duration::days(1))
+      .await
+      .unwrap();
+    let expire_at = board.expire_at.unwrap();
+    assert!((expire_at - (Utc::now() + Duration::days(1)).timestamp()).abs() < 60);
+    let extended = extend(&db, &board.id, Duration::days(10)).await.unwrap();
+    assert!(extended.expire_at.unwrap() > expire_at + Duration::days(8).num_seconds());
+    delete(&db, &board.id).await.unwrap();
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn purge_expired_removes_only_expired_boards() {
+    let db = emulator_db().await;
+    let expired = new(&db, &test_participant(), board_msg("Expired"), Duration::days(-1))
+      .await
+      .unwrap();
+    let kept = new(&db, &test_participant(), board_msg("Kept"), retention()).await.unwrap();
+    purge_expired(&db).await.unwrap();
+    assert!(matches!(
+      get_including_deleted(&db, &expired.id).await,
+      Err(Error::NotFound)
+    ));
+    assert!(get(&db, &kept.id).await.is_ok());
+    delete(&db, &kept.id).await.unwrap();
+  }
 }
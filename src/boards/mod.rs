@@ -3,8 +3,15 @@ pub mod models;
 pub mod routes;
 
 use firestore::FirestoreDb;
+use jwt_simple::prelude::{Claims, Duration, HS256Key, MACLike};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
 
+use crate::config::Config;
 use crate::error::Error;
+use crate::participants::db::is_board_member;
+use crate::participants::models::Participant;
 
 pub async fn assert_cards_allowed(firestore: &FirestoreDb, board_id: &String) -> Result<(), Error> {
   let board = db::get(firestore, board_id).await?;
@@ -25,6 +32,158 @@ pub async fn assert_voting_allowed(
   }
 }
 
+pub async fn assert_multi_vote_allowed(
+  firestore: &FirestoreDb,
+  board_id: &String,
+) -> Result<(), Error> {
+  let board = db::get(firestore, board_id).await?;
+  if !board.voting_open {
+    return Err(Error::Forbidden);
+  }
+  match board.multi_vote {
+    true => Ok(()),
+    false => Err(Error::BadRequest("Multi-vote is not enabled on this board.".into())),
+  }
+}
+
+pub async fn assert_board_member(
+  firestore: &FirestoreDb,
+  participant: &Participant,
+  board_id: &String,
+) -> Result<(), Error> {
+  db::get(firestore, board_id).await?;
+  match is_board_member(firestore, participant, board_id).await? {
+    true => Ok(()),
+    false => Err(Error::Forbidden),
+  }
+}
+
+// Private boards can only be used by their members, matching what firestore.rules
+// enforces for direct reads. Anyone with the id can use the rest.
+pub async fn assert_board_access(
+  firestore: &FirestoreDb,
+  participant: &Participant,
+  board_id: &String,
+) -> Result<models::Board, Error> {
+  let board = db::get(firestore, board_id).await?;
+  if board.private && !is_board_member(firestore, participant, board_id).await? {
+    return Err(Error::Forbidden);
+  }
+  Ok(board)
+}
+
+const INVITE_LIFETIME_DAYS: u64 = 7;
+
+pub fn new_invite(config: &Config, board_id: &str) -> Result<models::InviteResponse, Error> {
+  let key = HS256Key::from_bytes(&config.secret_key);
+  let claims = Claims::with_custom_claims(
+    models::InviteClaims {
+      board_id: board_id.to_string(),
+    },
+    Duration::from_days(INVITE_LIFETIME_DAYS),
+  );
+  let expires_at = claims.expires_at.map(|expires_at| expires_at.as_secs() as i64);
+  Ok(models::InviteResponse {
+    token: key.authenticate(claims)?,
+    expires_at: expires_at.unwrap_or_default(),
+  })
+}
+
+// Tokens that are forged, expired or issued for another board are all refused alike.
+pub fn check_invite(config: &Config, board_id: &str, token: &str) -> Result<(), Error> {
+  let key = HS256Key::from_bytes(&config.secret_key);
+  match key.verify_token::<models::InviteClaims>(token, None) {
+    Ok(claims) if claims.custom.board_id == board_id => Ok(()),
+    _ => Err(Error::Forbidden),
+  }
+}
+
+const JOIN_CODE_MISSES: u32 = 10;
+const JOIN_CODE_MISS_WINDOW: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+
+// Counts the join codes each participant and each client address has tried that didn't
+// match a board, so codes can't be found by trying them in turn. A guess is refused once
+// either has too many misses, so neither a new session nor a new address gets around it.
+// The counts are kept in memory, so the limit applies per instance.
+#[derive(Default)]
+pub struct JoinCodeGuard {
+  misses: Mutex<HashMap<String, (Instant, u32)>>,
+}
+
+impl JoinCodeGuard {
+  pub fn check(&self, keys: &[String]) -> Result<(), Error> {
+    let misses = self.misses.lock().unwrap();
+    let refused = keys.iter().any(|key| {
+      matches!(
+        misses.get(key),
+        Some((since, count))
+          if since.elapsed() < JOIN_CODE_MISS_WINDOW && *count >= JOIN_CODE_MISSES
+      )
+    });
+    match refused {
+      true => Err(Error::TooManyRequests),
+      false => Ok(()),
+    }
+  }
+
+  pub fn record_miss(&self, keys: &[String]) {
+    let mut misses = self.misses.lock().unwrap();
+    misses.retain(|_, (since, _)| since.elapsed() < JOIN_CODE_MISS_WINDOW);
+    for key in keys {
+      misses.entry(key.clone()).or_insert((Instant::now(), 0)).1 += 1;
+    }
+  }
+}
+
 pub async fn get_board(firestore: &FirestoreDb, board_id: &String) -> Result<models::Board, Error> {
   db::get(firestore, board_id).await
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::integration_tests::test_config;
+
+  #[test]
+  fn join_code_guard_refuses_a_client_after_too_many_misses() {
+    let guard = JoinCodeGuard::default();
+    let keys = |participant: &str, address: &str| {
+      vec![format!("participant:{participant}"), format!("address:{address}")]
+    };
+    for _ in 0..JOIN_CODE_MISSES {
+      assert!(guard.check(&keys("p1", "10.0.0.1")).is_ok());
+      guard.record_miss(&keys("p1", "10.0.0.1"));
+    }
+    assert!(matches!(guard.check(&keys("p1", "10.0.0.1")), Err(Error::TooManyRequests)));
+    assert!(matches!(guard.check(&keys("p1", "10.0.0.2")), Err(Error::TooManyRequests)));
+    assert!(matches!(guard.check(&keys("p2", "10.0.0.1")), Err(Error::TooManyRequests)));
+    assert!(guard.check(&keys("p2", "10.0.0.2")).is_ok());
+  }
+
+  #[test]
+  fn invite_admits_its_board_only() {
+    let config = test_config();
+    let invite = new_invite(&config, "board1").unwrap();
+    assert!(invite.expires_at > chrono::Utc::now().timestamp());
+    assert!(check_invite(&config, "board1", &invite.token).is_ok());
+    assert!(matches!(
+      check_invite(&config, "board2", &invite.token),
+      Err(Error::Forbidden)
+    ));
+  }
+
+  #[test]
+  fn invite_signed_with_another_key_is_refused() {
+    let mut config = test_config();
+    let invite = new_invite(&config, "board1").unwrap();
+    config.secret_key = vec![1_u8; 64];
+    assert!(matches!(
+      check_invite(&config, "board1", &invite.token),
+      Err(Error::Forbidden)
+    ));
+    assert!(matches!(
+      check_invite(&config, "board1", "not-a-token"),
+      Err(Error::Forbidden)
+    ));
+  }
+}
@@ -1,9 +1,15 @@
-use chrono::Utc;
+use chrono::{DateTime, Duration, Utc};
 use firestore::{FirestoreReference, FirestoreTimestamp};
 use serde::{Deserialize, Serialize};
 use serde_json::Map;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::str::FromStr;
 
-#[derive(Deserialize, Serialize)]
+use crate::cards::models::{votes_cast, Card};
+use crate::error::Error;
+use crate::participants::models::Member;
+
+#[derive(Deserialize, Serialize, Default)]
 pub struct BoardMessage {
   #[serde(skip_serializing_if = "Option::is_none")]
   pub name: Option<String>,
@@ -17,6 +23,79 @@ pub struct BoardMessage {
   pub data: Option<serde_json::Value>,
   #[serde(skip_serializing_if = "Option::is_none")]
   pub open_permission: Option<bool>,
+  // Zero lifts the limit, as a field can't be cleared through an update.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub max_votes_per_participant: Option<u32>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub multi_vote: Option<bool>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub blind_voting: Option<bool>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub anonymous: Option<bool>,
+  // Private boards can only be joined through an invite from the owner.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub private: Option<bool>,
+  // Only read on creation: copies unfinished actions over from an earlier board,
+  // or turns the cards of one of its columns into actions when a column is named.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub previous_board_id: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub carry_over_column: Option<String>,
+  // Only read on creation: names a built-in or saved template whose columns are
+  // created with the board.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub template: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Default)]
+pub struct BoardCloneMessage {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub name: Option<String>,
+  // Columns are always copied; cards only when asked for.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub include_cards: Option<bool>,
+  // Keeps the votes and reactions of copied cards.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub include_votes: Option<bool>,
+}
+
+#[derive(Deserialize, Serialize, Default)]
+pub struct BoardTransferMessage {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub participant_id: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Default)]
+pub struct BoardJoinMessage {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub invite: Option<String>,
+}
+
+// Signed into invite tokens so that a token only admits its holder to one board.
+#[derive(Deserialize, Serialize)]
+pub struct InviteClaims {
+  pub board_id: String,
+}
+
+#[derive(Serialize)]
+pub struct InviteResponse {
+  pub token: String,
+  pub expires_at: i64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OwnershipTransfer {
+  pub from: FirestoreReference,
+  pub to: FirestoreReference,
+  pub transferred_at: i64,
+}
+
+// Every change of owner is appended to the board, so it can be traced later.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct OwnershipTransferInFirestore {
+  pub from: FirestoreReference,
+  pub to: FirestoreReference,
+  pub transferred_at: FirestoreTimestamp,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -30,6 +109,16 @@ pub struct Board {
   pub owner: FirestoreReference,
   pub open_permission: bool,
   pub data: serde_json::Value,
+  pub max_votes_per_participant: Option<u32>,
+  pub multi_vote: bool,
+  pub blind_voting: bool,
+  pub anonymous: bool,
+  pub private: bool,
+  pub join_code: Option<String>,
+  pub deleted_at: Option<i64>,
+  pub expire_at: Option<i64>,
+  pub facilitators: Vec<FirestoreReference>,
+  pub ownership_transfers: Vec<OwnershipTransfer>,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -42,6 +131,13 @@ pub struct NewBoard {
   pub owner: Option<FirestoreReference>,
   pub open_permission: bool,
   pub data: serde_json::Value,
+  pub max_votes_per_participant: Option<u32>,
+  pub multi_vote: bool,
+  pub blind_voting: bool,
+  pub anonymous: bool,
+  pub private: bool,
+  pub expire_at: Option<FirestoreTimestamp>,
+  pub join_code: Option<String>,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -56,6 +152,122 @@ pub struct BoardInFirestore {
   pub owner: FirestoreReference,
   pub open_permission: Option<bool>,
   pub data: serde_json::Value,
+  pub max_votes_per_participant: Option<u32>,
+  pub multi_vote: Option<bool>,
+  pub blind_voting: Option<bool>,
+  pub anonymous: Option<bool>,
+  pub private: Option<bool>,
+  pub join_code: Option<String>,
+  pub deleted_at: Option<FirestoreTimestamp>,
+  pub expire_at: Option<FirestoreTimestamp>,
+  pub facilitators: Option<Vec<FirestoreReference>>,
+  pub ownership_transfers: Option<Vec<OwnershipTransferInFirestore>>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct BoardOwnership {
+  pub owner: FirestoreReference,
+}
+
+// Cleared when the owner disables the board's join code.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct BoardJoinCode {
+  pub join_code: Option<String>,
+}
+
+// Join codes are indexed in their own collection, keyed by the code, so looking one
+// up or claiming it never has to scan the boards.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct JoinCodeInFirestore {
+  pub board: FirestoreReference,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct BoardCreatedAtInFirestore {
+  pub _firestore_id: String,
+  pub _firestore_created: FirestoreTimestamp,
+  pub created_at: Option<FirestoreTimestamp>,
+  pub deleted_at: Option<FirestoreTimestamp>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct BoardExpiry {
+  pub expire_at: FirestoreTimestamp,
+}
+
+// Set when a board is moved to the trash, and cleared again when it's restored.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct BoardDeletion {
+  pub deleted_at: Option<FirestoreTimestamp>,
+}
+
+// Facilitators run the board alongside its owner, but only the owner can delete
+// the board or change who facilitates it.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BoardRole {
+  Owner,
+  Facilitator,
+  Participant,
+}
+
+#[derive(Serialize)]
+pub struct FacilitatorsResponse {
+  pub facilitators: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct BoardListQuery {
+  pub limit: Option<usize>,
+  pub cursor: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct BoardListResponse {
+  pub boards: Vec<BoardResponse>,
+  pub next_cursor: Option<String>,
+}
+
+// Position of the last board on a page, encoded as `<created_at micros>:<board id>`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct BoardCursor {
+  pub created_at: i64,
+  pub id: String,
+}
+
+impl BoardCursor {
+  pub fn new(created_at: DateTime<Utc>, id: String) -> BoardCursor {
+    BoardCursor {
+      created_at: created_at.timestamp_micros(),
+      id,
+    }
+  }
+
+  pub fn created_at(&self) -> DateTime<Utc> {
+    DateTime::from_timestamp_micros(self.created_at).unwrap_or_default()
+  }
+}
+
+impl Display for BoardCursor {
+  fn fmt(&self, f: &mut Formatter) -> FmtResult {
+    write!(f, "{}:{}", self.created_at, self.id)
+  }
+}
+
+impl FromStr for BoardCursor {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let invalid = || Error::BadRequest("Invalid cursor.".into());
+    let (created_at, id) = s.split_once(':').ok_or_else(invalid)?;
+    if id.is_empty() {
+      return Err(invalid());
+    }
+    Ok(BoardCursor {
+      created_at: created_at.parse().map_err(|_| invalid())?,
+      id: id.into(),
+    })
+  }
 }
 
 impl From<BoardMessage> for NewBoard {
@@ -71,6 +283,36 @@ impl From<BoardMessage> for NewBoard {
       data: board
         .data
         .unwrap_or_else(|| serde_json::Value::Object(Map::new())),
+      max_votes_per_participant: board.max_votes_per_participant,
+      multi_vote: board.multi_vote.unwrap_or(false),
+      blind_voting: board.blind_voting.unwrap_or(false),
+      anonymous: board.anonymous.unwrap_or(false),
+      private: board.private.unwrap_or(false),
+      expire_at: None,
+      join_code: None,
+    }
+  }
+}
+
+impl NewBoard {
+  // Copies the settings of an existing board, which the caller then owns.
+  pub fn from_board(board: Board, name: Option<String>) -> NewBoard {
+    NewBoard {
+      name: name.unwrap_or(board.name),
+      cards_open: board.cards_open,
+      voting_open: board.voting_open,
+      ice_breaking: Some(board.ice_breaking),
+      created_at: FirestoreTimestamp(Utc::now()),
+      owner: None,
+      open_permission: board.open_permission,
+      data: board.data,
+      max_votes_per_participant: board.max_votes_per_participant,
+      multi_vote: board.multi_vote,
+      blind_voting: board.blind_voting,
+      anonymous: board.anonymous,
+      private: board.private,
+      expire_at: None,
+      join_code: None,
     }
   }
 }
@@ -91,10 +333,72 @@ impl From<BoardInFirestore> for Board {
       owner: board.owner,
       open_permission: board.open_permission.unwrap_or(false),
       data: board.data,
+      max_votes_per_participant: board.max_votes_per_participant.filter(|max| *max > 0),
+      multi_vote: board.multi_vote.unwrap_or(false),
+      blind_voting: board.blind_voting.unwrap_or(false),
+      anonymous: board.anonymous.unwrap_or(false),
+      private: board.private.unwrap_or(false),
+      join_code: board.join_code,
+      deleted_at: board.deleted_at.map(|deleted_at| deleted_at.0.timestamp()),
+      expire_at: board.expire_at.map(|expire_at| expire_at.0.timestamp()),
+      facilitators: board.facilitators.unwrap_or_default(),
+      ownership_transfers: board
+        .ownership_transfers
+        .unwrap_or_default()
+        .into_iter()
+        .map(|transfer| transfer.into())
+        .collect(),
+    }
+  }
+}
+
+impl From<OwnershipTransferInFirestore> for OwnershipTransfer {
+  fn from(transfer: OwnershipTransferInFirestore) -> Self {
+    OwnershipTransfer {
+      from: transfer.from,
+      to: transfer.to,
+      transferred_at: transfer.transferred_at.0.timestamp(),
     }
   }
 }
 
+impl Board {
+  pub fn role_of(&self, participant_id: &FirestoreReference) -> BoardRole {
+    if &self.owner == participant_id {
+      BoardRole::Owner
+    } else if self.facilitators.contains(participant_id) {
+      BoardRole::Facilitator
+    } else {
+      BoardRole::Participant
+    }
+  }
+
+  pub fn facilitated_by(&self, participant_id: &FirestoreReference) -> bool {
+    self.role_of(participant_id) != BoardRole::Participant
+  }
+
+  // Deleted boards can be restored until the window after their deletion has passed.
+  pub fn restorable(&self, window: Duration, now: DateTime<Utc>) -> bool {
+    self
+      .deleted_at
+      .is_some_and(|deleted_at| now.timestamp() < deleted_at + window.num_seconds())
+  }
+
+  // Vote totals stay hidden from everyone but the facilitators until voting closes.
+  pub fn hides_votes_from(&self, participant_id: &FirestoreReference) -> bool {
+    self.blind_voting && self.voting_open && !self.facilitated_by(participant_id)
+  }
+
+  // On anonymous boards only a card's owner gets to see who wrote it.
+  pub fn hides_author_from(
+    &self,
+    card_owner: &FirestoreReference,
+    participant_id: &FirestoreReference,
+  ) -> bool {
+    self.anonymous && card_owner != participant_id
+  }
+}
+
 #[derive(Deserialize, Serialize)]
 pub struct BoardResponse {
   pub id: String,
@@ -104,12 +408,83 @@ pub struct BoardResponse {
   pub ice_breaking: String,
   pub created_at: i64,
   pub owner: bool,
+  pub role: BoardRole,
   pub open_permission: bool,
   pub data: serde_json::Value,
+  pub max_votes_per_participant: Option<u32>,
+  pub multi_vote: bool,
+  pub blind_voting: bool,
+  pub anonymous: bool,
+  pub private: bool,
+  pub join_code: Option<String>,
+  pub expire_at: Option<i64>,
+}
+
+// How each member took part in the board. Participants have no name of their own,
+// so the author name on their latest card stands in for one.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct MemberResponse {
+  pub id: String,
+  pub name: Option<String>,
+  pub role: BoardRole,
+  pub joined_at: i64,
+  pub last_seen: i64,
+  pub cards: usize,
+  pub votes: u32,
+  pub reactions: usize,
+}
+
+impl MemberResponse {
+  pub fn from_member(
+    member: Member,
+    board: &Board,
+    cards: &[Card],
+    participant_id: &FirestoreReference,
+  ) -> MemberResponse {
+    let owned: Vec<&Card> = cards
+      .iter()
+      .filter(|card| card.owner == member.participant)
+      .collect();
+    let name = match board.hides_author_from(&member.participant, participant_id) {
+      true => None,
+      false => owned
+        .iter()
+        .filter(|card| !card.author.is_empty())
+        .max_by_key(|card| card.created_at)
+        .map(|card| card.author.clone()),
+    };
+    MemberResponse {
+      name,
+      role: board.role_of(&member.participant),
+      joined_at: member.joined_at,
+      last_seen: member.last_seen,
+      cards: owned.len(),
+      votes: votes_cast(cards, &member.participant),
+      reactions: cards
+        .iter()
+        .flat_map(|card| card.reactions.values())
+        .filter(|participants| participants.contains(&member.participant.0))
+        .count(),
+      id: member.id,
+    }
+  }
+}
+
+impl FacilitatorsResponse {
+  pub fn from_board(board: &Board) -> FacilitatorsResponse {
+    FacilitatorsResponse {
+      facilitators: board
+        .facilitators
+        .iter()
+        .map(|facilitator| facilitator.0.split('/').next_back().unwrap().to_string())
+        .collect(),
+    }
+  }
 }
 
 impl BoardResponse {
   pub fn from_board(board: Board, participant_id: &FirestoreReference) -> BoardResponse {
+    let role = board.role_of(participant_id);
     BoardResponse {
       id: board.id,
       name: board.name,
@@ -118,8 +493,16 @@ impl BoardResponse {
       ice_breaking: board.ice_breaking,
       created_at: board.created_at,
       owner: &board.owner == participant_id,
+      role,
       open_permission: board.open_permission,
       data: board.data,
+      max_votes_per_participant: board.max_votes_per_participant,
+      multi_vote: board.multi_vote,
+      blind_voting: board.blind_voting,
+      anonymous: board.anonymous,
+      private: board.private,
+      join_code: board.join_code,
+      expire_at: board.expire_at,
     }
   }
 }
@@ -146,9 +529,40 @@ mod tests {
       owner: ref_(owner),
       open_permission: None,
       data: serde_json::Value::Object(serde_json::Map::new()),
+      max_votes_per_participant: None,
+      multi_vote: None,
+      blind_voting: None,
+      anonymous: None,
+      private: None,
+      join_code: None,
+      deleted_at: None,
+      expire_at: None,
+      facilitators: None,
+      ownership_transfers: None,
     }
   }
 
+  #[test]
+  fn new_board_from_board_copies_settings_but_not_owner() {
+    let mut in_firestore = board_in_firestore("b1", "participants/user1");
+    in_firestore.multi_vote = Some(true);
+    in_firestore.max_votes_per_participant = Some(3);
+    let b = NewBoard::from_board(in_firestore.into(), None);
+    assert_eq!(b.name, "Test Board");
+    assert!(!b.voting_open);
+    assert!(b.multi_vote);
+    assert_eq!(b.max_votes_per_participant, Some(3));
+    assert_eq!(b.ice_breaking.as_deref(), Some("How are you?"));
+    assert!(b.owner.is_none());
+  }
+
+  #[test]
+  fn new_board_from_board_takes_new_name() {
+    let board: Board = board_in_firestore("b1", "participants/user1").into();
+    let b = NewBoard::from_board(board, Some("Team B retro".into()));
+    assert_eq!(b.name, "Team B retro");
+  }
+
   #[test]
   fn board_message_all_none_uses_defaults() {
     let msg = BoardMessage {
@@ -158,6 +572,7 @@ mod tests {
       ice_breaking: None,
       data: None,
       open_permission: None,
+      ..Default::default()
     };
     let b: NewBoard = msg.into();
     assert_eq!(b.name, "");
@@ -177,6 +592,7 @@ mod tests {
       ice_breaking: Some("Icebreaker!".to_string()),
       data: Some(serde_json::json!({"key": "value"})),
       open_permission: None,
+      ..Default::default()
     };
     let b: NewBoard = msg.into();
     assert_eq!(b.name, "My Retro");
@@ -253,6 +669,7 @@ mod tests {
       ice_breaking: None,
       data: None,
       open_permission: None,
+      ..Default::default()
     };
     let b: NewBoard = msg.into();
     assert!(!b.open_permission);
@@ -267,11 +684,157 @@ mod tests {
       ice_breaking: None,
       data: None,
       open_permission: Some(true),
+      ..Default::default()
     };
     let b: NewBoard = msg.into();
     assert!(b.open_permission);
   }
 
+  #[test]
+  fn board_in_firestore_zero_vote_budget_means_unlimited() {
+    let mut raw = board_in_firestore("b1", "participants/user1");
+    raw.max_votes_per_participant = Some(0);
+    let board: Board = raw.into();
+    assert!(board.max_votes_per_participant.is_none());
+  }
+
+  #[test]
+  fn board_in_firestore_vote_budget_preserved() {
+    let mut raw = board_in_firestore("b1", "participants/user1");
+    raw.max_votes_per_participant = Some(5);
+    let board: Board = raw.into();
+    assert_eq!(board.max_votes_per_participant, Some(5));
+  }
+
+  #[test]
+  fn blind_voting_hides_votes_from_non_owner_while_voting_open() {
+    let mut raw = board_in_firestore("b1", "participants/owner");
+    raw.blind_voting = Some(true);
+    raw.voting_open = true;
+    let board: Board = raw.into();
+    assert!(board.hides_votes_from(&ref_("participants/other")));
+    assert!(!board.hides_votes_from(&ref_("participants/owner")));
+  }
+
+  #[test]
+  fn role_distinguishes_owner_facilitators_and_participants() {
+    let mut raw = board_in_firestore("b1", "participants/owner");
+    raw.facilitators = Some(vec![ref_("participants/facilitator")]);
+    let board: Board = raw.into();
+    assert_eq!(board.role_of(&ref_("participants/owner")), BoardRole::Owner);
+    assert_eq!(board.role_of(&ref_("participants/facilitator")), BoardRole::Facilitator);
+    assert_eq!(board.role_of(&ref_("participants/other")), BoardRole::Participant);
+    let resp = BoardResponse::from_board(board, &ref_("participants/facilitator"));
+    assert!(!resp.owner);
+    assert_eq!(resp.role, BoardRole::Facilitator);
+  }
+
+  #[test]
+  fn ownership_transfers_carry_unix_timestamps() {
+    let mut raw = board_in_firestore("b1", "participants/new");
+    let transferred_at = Utc::now();
+    raw.ownership_transfers = Some(vec![OwnershipTransferInFirestore {
+      from: ref_("participants/old"),
+      to: ref_("participants/new"),
+      transferred_at: FirestoreTimestamp(transferred_at),
+    }]);
+    let board: Board = raw.into();
+    assert_eq!(
+      board.ownership_transfers,
+      vec![OwnershipTransfer {
+        from: ref_("participants/old"),
+        to: ref_("participants/new"),
+        transferred_at: transferred_at.timestamp(),
+      }]
+    );
+  }
+
+  #[test]
+  fn blind_voting_reveals_votes_to_facilitators() {
+    let mut raw = board_in_firestore("b1", "participants/owner");
+    raw.blind_voting = Some(true);
+    raw.voting_open = true;
+    raw.facilitators = Some(vec![ref_("participants/facilitator")]);
+    let board: Board = raw.into();
+    assert!(!board.hides_votes_from(&ref_("participants/facilitator")));
+  }
+
+  #[test]
+  fn facilitators_response_lists_participant_ids() {
+    let mut raw = board_in_firestore("b1", "participants/owner");
+    raw.facilitators = Some(vec![ref_("projects/p/databases/d/documents/participants/f1")]);
+    let board: Board = raw.into();
+    assert_eq!(FacilitatorsResponse::from_board(&board).facilitators, vec!["f1"]);
+  }
+
+  #[test]
+  fn blind_voting_reveals_votes_once_voting_closes() {
+    let mut raw = board_in_firestore("b1", "participants/owner");
+    raw.blind_voting = Some(true);
+    raw.voting_open = false;
+    let board: Board = raw.into();
+    assert!(!board.hides_votes_from(&ref_("participants/other")));
+  }
+
+  #[test]
+  fn votes_visible_without_blind_voting() {
+    let mut raw = board_in_firestore("b1", "participants/owner");
+    raw.voting_open = true;
+    let board: Board = raw.into();
+    assert!(!board.hides_votes_from(&ref_("participants/other")));
+  }
+
+  #[test]
+  fn anonymous_board_hides_author_from_everyone_but_card_owner() {
+    let mut raw = board_in_firestore("b1", "participants/owner");
+    raw.anonymous = Some(true);
+    let board: Board = raw.into();
+    let writer = ref_("participants/writer");
+    assert!(board.hides_author_from(&writer, &ref_("participants/owner")));
+    assert!(!board.hides_author_from(&writer, &writer));
+  }
+
+  #[test]
+  fn authors_visible_on_non_anonymous_board() {
+    let board: Board = board_in_firestore("b1", "participants/owner").into();
+    let writer = ref_("participants/writer");
+    assert!(!board.hides_author_from(&writer, &ref_("participants/other")));
+  }
+
+  #[test]
+  fn board_cursor_round_trips_through_string() {
+    let cursor = BoardCursor {
+      created_at: 1_600_000_000_123_456,
+      id: "abc123".to_string(),
+    };
+    let parsed: BoardCursor = cursor.to_string().parse().unwrap();
+    assert_eq!(parsed, cursor);
+  }
+
+  #[test]
+  fn board_cursor_without_separator_is_bad_request() {
+    assert!(matches!(
+      "1600000000".parse::<BoardCursor>(),
+      Err(Error::BadRequest(_))
+    ));
+  }
+
+  #[test]
+  fn board_cursor_with_non_numeric_timestamp_is_bad_request() {
+    assert!(matches!(
+      "yesterday:abc123".parse::<BoardCursor>(),
+      Err(Error::BadRequest(_))
+    ));
+  }
+
+  #[test]
+  fn board_cursor_with_empty_id_is_bad_request() {
+    assert!(matches!(
+      "1600000000:".parse::<BoardCursor>(),
+      Err(Error::BadRequest(_))
+    ));
+  }
+
   #[test]
   fn board_response_fields_match_board() {
     let participant = ref_("participants/user1");
@@ -283,4 +846,91 @@ mod tests {
     assert!(!resp.voting_open);
     assert_eq!(resp.ice_breaking, "How are you?");
   }
+
+  #[test]
+  fn deleted_board_is_restorable_within_window() {
+    let mut board: Board = board_in_firestore("b1", "participants/user1").into();
+    board.deleted_at = Some(1_000_000);
+    let now = DateTime::from_timestamp(1_000_000 + 60, 0).unwrap();
+    assert!(board.restorable(Duration::days(1), now));
+  }
+
+  #[test]
+  fn deleted_board_is_not_restorable_after_window() {
+    let mut board: Board = board_in_firestore("b1", "participants/user1").into();
+    board.deleted_at = Some(1_000_000);
+    let now = DateTime::from_timestamp(1_000_000 + 2 * 24 * 60 * 60, 0).unwrap();
+    assert!(!board.restorable(Duration::days(1), now));
+  }
+
+  #[test]
+  fn live_board_is_not_restorable() {
+    let board: Board = board_in_firestore("b1", "participants/user1").into();
+    assert!(!board.restorable(Duration::days(1), Utc::now()));
+  }
+
+  fn card(owner: &str, author: &str, created_at: i64) -> Card {
+    Card {
+      id: format!("card-{created_at}"),
+      column: ref_("boards/b1/columns/c1"),
+      owner: ref_(owner),
+      author: author.to_string(),
+      text: "A card".to_string(),
+      created_at,
+      votes: vec![],
+      vote_counts: std::collections::HashMap::new(),
+      reactions: std::collections::HashMap::new(),
+      parent: None,
+    }
+  }
+
+  fn member(participant: &str) -> Member {
+    Member {
+      id: participant.split('/').next_back().unwrap().to_string(),
+      participant: ref_(participant),
+      joined_at: 10,
+      last_seen: 20,
+    }
+  }
+
+  #[test]
+  fn member_response_tallies_cards_votes_and_reactions() {
+    let board: Board = board_in_firestore("b1", "participants/user1").into();
+    let mut first = card("participants/user2", "Sam", 1);
+    first.votes = vec!["participants/user2".into()];
+    let mut second = card("participants/user2", "", 2);
+    second.reactions.insert("🎉".into(), vec!["participants/user2".into()]);
+    let mut other = card("participants/user1", "Alex", 3);
+    other.vote_counts.insert("user2".into(), 2);
+    let cards = vec![first, second, other];
+
+    let resp = MemberResponse::from_member(
+      member("participants/user2"),
+      &board,
+      &cards,
+      &ref_("participants/user1"),
+    );
+    assert_eq!(resp.id, "user2");
+    assert_eq!(resp.name.as_deref(), Some("Sam"));
+    assert_eq!(resp.role, BoardRole::Participant);
+    assert_eq!((resp.joined_at, resp.last_seen), (10, 20));
+    assert_eq!(resp.cards, 2);
+    assert_eq!(resp.votes, 3);
+    assert_eq!(resp.reactions, 1);
+  }
+
+  #[test]
+  fn member_response_hides_names_on_anonymous_boards() {
+    let mut board: Board = board_in_firestore("b1", "participants/user1").into();
+    board.anonymous = true;
+    let cards = vec![card("participants/user2", "Sam", 1)];
+    let resp = MemberResponse::from_member(
+      member("participants/user2"),
+      &board,
+      &cards,
+      &ref_("participants/user1"),
+    );
+    assert!(resp.name.is_none());
+    assert_eq!(resp.cards, 1);
+  }
 }
@@ -1,31 +1,78 @@
-use actix_web::{delete, get, patch, post, web, HttpResponse};
+use actix_web::{delete, get, patch, post, put, web, HttpRequest, HttpResponse};
+use chrono::Utc;
 use firestore::FirestoreDb;
 use firestore::FirestoreReference;
-use futures::future::join;
 
 use super::db;
 use super::models::*;
+use super::JoinCodeGuard;
+use crate::action_items;
+use crate::cards;
+use crate::cloudrun::client_address;
+use crate::columns::get_columns;
+use crate::config::Config;
 use crate::error::Error;
+use crate::events::models::BoardEvent;
+use crate::events::Broker;
 use crate::participants::db::*;
 use crate::participants::models::Participant;
+use crate::templates;
 
-fn check_delete_permission(board: &Board, participant: &FirestoreReference) -> Result<(), Error> {
+const DEFAULT_PAGE_SIZE: usize = 20;
+const MAX_PAGE_SIZE: usize = 100;
+
+fn check_owner_permission(board: &Board, participant: &FirestoreReference) -> Result<(), Error> {
   if board.owner != *participant {
     return Err(Error::Forbidden);
   }
   Ok(())
 }
 
+fn check_facilitator_permission(
+  board: &Board,
+  participant: &FirestoreReference,
+) -> Result<(), Error> {
+  if !board.facilitated_by(participant) {
+    return Err(Error::Forbidden);
+  }
+  Ok(())
+}
+
+// Who took part is visible to anyone who can run the board, which on boards with open
+// permission is everyone.
+fn check_participants_permission(
+  board: &Board,
+  participant: &FirestoreReference,
+) -> Result<(), Error> {
+  if !board.facilitated_by(participant) && !board.open_permission {
+    return Err(Error::Forbidden);
+  }
+  Ok(())
+}
+
 fn check_update_permission(
   board: &Board,
   participant: &FirestoreReference,
   message: &BoardMessage,
 ) -> Result<(), Error> {
-  let is_owner = board.owner == *participant;
-  if !is_owner && !board.open_permission {
+  let facilitates = board.facilitated_by(participant);
+  if !facilitates && !board.open_permission {
     return Err(Error::Forbidden);
   }
-  if !is_owner && message.open_permission.is_some() {
+  // Showing authors on an anonymous board reveals them to everyone, so it's left to
+  // whoever runs the board.
+  if !facilitates && (message.open_permission.is_some() || message.anonymous.is_some()) {
+    return Err(Error::Forbidden);
+  }
+  // The voting rules, and when blind votes are revealed, are the facilitators' call.
+  let changes_voting = message.max_votes_per_participant.is_some()
+    || message.multi_vote.is_some()
+    || message.blind_voting.is_some()
+    || (board.blind_voting && message.voting_open.is_some());
+  if !facilitates && changes_voting {
+    return Err(Error::Forbidden);
+  }
+  if board.owner != *participant && message.private.is_some() {
     return Err(Error::Forbidden);
   }
   Ok(())
@@ -34,14 +81,56 @@ fn check_update_permission(
 #[post("boards")]
 pub async fn new(
   firestore: web::Data<FirestoreDb>,
+  config: web::Data<Config>,
   participant: Participant,
   board_message: web::Json<BoardMessage>,
 ) -> Result<HttpResponse, Error> {
   let mut board_message = board_message.into_inner();
   board_message.voting_open.get_or_insert(true);
   board_message.cards_open.get_or_insert(true);
-  let board = db::new(&firestore, &participant, board_message).await?;
-  add_participant_board(&firestore, &participant, &board.id).await?;
+  let previous_board_id = board_message.previous_board_id.take();
+  let carry_over_column = board_message.carry_over_column.take();
+  let template = board_message.template.take();
+  if let Some(previous_board_id) = &previous_board_id {
+    action_items::assert_carry_over_allowed(
+      &firestore,
+      &participant,
+      previous_board_id,
+      carry_over_column.as_ref(),
+    )
+    .await?;
+  }
+  let board = match template {
+    Some(template) => {
+      let columns = templates::template_columns(&firestore, &template).await?;
+      db::new_with_columns(
+        &firestore,
+        &participant,
+        board_message,
+        columns,
+        config.board_retention,
+      )
+      .await?
+    }
+    None => db::new(&firestore, &participant, board_message, config.board_retention).await?,
+  };
+  if let Some(previous_board_id) = &previous_board_id {
+    // A board whose carry-over failed is removed rather than left half made.
+    let carried_over = action_items::carry_over(
+      &firestore,
+      previous_board_id,
+      carry_over_column.as_ref(),
+      &board.id,
+    )
+    .await;
+    if let Err(e) = carried_over {
+      if let Err(cleanup) = db::delete(&firestore, &board.id).await {
+        error!("Failed to remove board {} after its carry-over failed: {}", board.id, cleanup);
+      }
+      return Err(e);
+    }
+  }
+  add_participant_board(&firestore, &participant, &board).await?;
   Ok(
     HttpResponse::Ok().json(BoardResponse::from_board(
       board,
@@ -55,47 +144,97 @@ pub async fn new(
   )
 }
 
-#[get("boards")]
-pub async fn list(
+#[post("boards/{board_id}/clone")]
+pub async fn clone(
   firestore: web::Data<FirestoreDb>,
+  config: web::Data<Config>,
   participant: Participant,
+  board_id: web::Path<String>,
+  clone_message: web::Json<BoardCloneMessage>,
 ) -> Result<HttpResponse, Error> {
-  let boards = db::list(&firestore, &participant).await?;
+  super::assert_board_member(&firestore, &participant, &board_id).await?;
+  let clone_message = clone_message.into_inner();
+  let board = db::get(&firestore, &board_id).await?;
+  let columns = get_columns(&firestore, &board_id).await?.into_values().collect();
+  let cards = match clone_message.include_cards.unwrap_or(false) {
+    true => cards::db::list(&firestore, &board_id).await?,
+    false => vec![],
+  };
+  let board = db::clone(
+    &firestore,
+    &participant,
+    NewBoard::from_board(board, clone_message.name),
+    columns,
+    cards,
+    clone_message.include_votes.unwrap_or(false),
+    config.board_retention,
+  )
+  .await?;
+  add_participant_board(&firestore, &participant, &board).await?;
   Ok(
-    HttpResponse::Ok().json(
-      boards
-        .into_iter()
-        .map(|board| {
-          BoardResponse::from_board(
-            board,
-            &FirestoreReference(
-              firestore
-                .parent_path("participants", &participant.id)
-                .unwrap()
-                .into(),
-            ),
-          )
-        })
-        .collect::<Vec<BoardResponse>>(),
-    ),
+    HttpResponse::Ok().json(BoardResponse::from_board(
+      board,
+      &FirestoreReference(
+        firestore
+          .parent_path("participants", &participant.id)
+          .unwrap()
+          .into(),
+      ),
+    )),
   )
 }
 
+#[get("boards")]
+pub async fn list(
+  firestore: web::Data<FirestoreDb>,
+  participant: Participant,
+  query: web::Query<BoardListQuery>,
+) -> Result<HttpResponse, Error> {
+  let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+  let cursor = query
+    .cursor
+    .as_deref()
+    .map(str::parse::<BoardCursor>)
+    .transpose()?;
+  let (boards, next_cursor) = db::list(&firestore, &participant, limit, cursor.as_ref()).await?;
+  Ok(HttpResponse::Ok().json(BoardListResponse {
+    boards: boards
+      .into_iter()
+      .map(|board| {
+        BoardResponse::from_board(
+          board,
+          &FirestoreReference(
+            firestore
+              .parent_path("participants", &participant.id)
+              .unwrap()
+              .into(),
+          ),
+        )
+      })
+      .collect(),
+    next_cursor: next_cursor.map(|cursor| cursor.to_string()),
+  }))
+}
+
 #[get("boards/{board_id}")]
 pub async fn get(
   firestore: web::Data<FirestoreDb>,
   participant: Participant,
   board_id: web::Path<String>,
 ) -> Result<HttpResponse, Error> {
-  let (register, board) = join(
-    add_participant_board(&firestore, &participant, &board_id),
-    db::get(&firestore, &board_id),
-  )
-  .await;
-  register?;
+  // Private boards are only joined through an invite, so they're never joined here.
+  let board = match db::get(&firestore, &board_id).await? {
+    board if board.private => {
+      super::assert_board_access(&firestore, &participant, &board_id).await?
+    }
+    board => {
+      add_participant_board(&firestore, &participant, &board).await?;
+      board
+    }
+  };
   Ok(
     HttpResponse::Ok().json(BoardResponse::from_board(
-      board?,
+      board,
       &FirestoreReference(
         firestore
           .parent_path("participants", &participant.id)
@@ -109,11 +248,12 @@ pub async fn get(
 #[patch("boards/{board_id}")]
 pub async fn update(
   firestore: web::Data<FirestoreDb>,
+  broker: web::Data<Broker>,
   participant: Participant,
   board_id: web::Path<String>,
   board_message: web::Json<BoardMessage>,
 ) -> Result<HttpResponse, Error> {
-  let board = db::get(&firestore, &board_id).await?;
+  let board = super::assert_board_access(&firestore, &participant, &board_id).await?;
   let participant_reference = FirestoreReference(
     firestore
       .parent_path("participants", &participant.id)
@@ -123,6 +263,7 @@ pub async fn update(
   let board_message = board_message.into_inner();
   check_update_permission(&board, &participant_reference, &board_message)?;
   let board = db::update(&firestore, &board_id, board_message).await?;
+  broker.publish(&board_id, BoardEvent::BoardChanged);
   Ok(HttpResponse::Ok().json(BoardResponse::from_board(board, &participant_reference)))
 }
 
@@ -132,18 +273,352 @@ pub async fn delete(
   participant: Participant,
   board_id: web::Path<String>,
 ) -> Result<HttpResponse, Error> {
-  let board = db::get(&firestore, &board_id).await?;
+  let board = super::assert_board_access(&firestore, &participant, &board_id).await?;
   let participant_reference = FirestoreReference(
     firestore
       .parent_path("participants", &participant.id)
       .unwrap()
       .into(),
   );
-  check_delete_permission(&board, &participant_reference)?;
-  db::delete(&firestore, &board_id).await?;
+  check_owner_permission(&board, &participant_reference)?;
+  db::move_to_trash(&firestore, &board_id).await?;
   Ok(HttpResponse::Ok().finish())
 }
 
+#[post("boards/{board_id}/extend")]
+pub async fn extend(
+  firestore: web::Data<FirestoreDb>,
+  config: web::Data<Config>,
+  participant: Participant,
+  board_id: web::Path<String>,
+) -> Result<HttpResponse, Error> {
+  let board = super::assert_board_access(&firestore, &participant, &board_id).await?;
+  let participant_reference = FirestoreReference(
+    firestore
+      .parent_path("participants", &participant.id)
+      .unwrap()
+      .into(),
+  );
+  check_facilitator_permission(&board, &participant_reference)?;
+  let board = db::extend(&firestore, &board_id, config.board_retention).await?;
+  Ok(HttpResponse::Ok().json(BoardResponse::from_board(board, &participant_reference)))
+}
+
+#[post("boards/{board_id}/restore")]
+pub async fn restore(
+  firestore: web::Data<FirestoreDb>,
+  config: web::Data<Config>,
+  participant: Participant,
+  board_id: web::Path<String>,
+) -> Result<HttpResponse, Error> {
+  let board = db::get_including_deleted(&firestore, &board_id).await?;
+  let participant_reference = FirestoreReference(
+    firestore
+      .parent_path("participants", &participant.id)
+      .unwrap()
+      .into(),
+  );
+  check_owner_permission(&board, &participant_reference)?;
+  if board.deleted_at.is_none() {
+    return Err(Error::BadRequest("Board is not deleted.".into()));
+  }
+  if !board.restorable(config.board_restore_window, Utc::now()) {
+    return Err(Error::NotFound);
+  }
+  let board = db::restore(&firestore, &board_id).await?;
+  Ok(HttpResponse::Ok().json(BoardResponse::from_board(board, &participant_reference)))
+}
+
+// Roles can only be handed to participants who have already joined the board.
+async fn has_joined(
+  firestore: &FirestoreDb,
+  participant_id: &str,
+  board_id: &String,
+) -> Result<bool, Error> {
+  let participant = Participant {
+    id: participant_id.to_string(),
+  };
+  is_board_member(firestore, &participant, board_id).await
+}
+
+#[post("boards/{board_id}/invites")]
+pub async fn invite(
+  firestore: web::Data<FirestoreDb>,
+  config: web::Data<Config>,
+  participant: Participant,
+  board_id: web::Path<String>,
+) -> Result<HttpResponse, Error> {
+  let board = db::get(&firestore, &board_id).await?;
+  let participant_reference = FirestoreReference(
+    firestore
+      .parent_path("participants", &participant.id)
+      .unwrap()
+      .into(),
+  );
+  check_owner_permission(&board, &participant_reference)?;
+  Ok(HttpResponse::Ok().json(super::new_invite(&config, &board_id)?))
+}
+
+#[post("boards/{board_id}/join")]
+pub async fn join(
+  firestore: web::Data<FirestoreDb>,
+  config: web::Data<Config>,
+  participant: Participant,
+  board_id: web::Path<String>,
+  join_message: web::Json<BoardJoinMessage>,
+) -> Result<HttpResponse, Error> {
+  let board = db::get(&firestore, &board_id).await?;
+  if board.private {
+    let token = join_message.into_inner().invite.ok_or(Error::Forbidden)?;
+    super::check_invite(&config, &board_id, &token)?;
+  }
+  add_participant_board(&firestore, &participant, &board).await?;
+  Ok(
+    HttpResponse::Ok().json(BoardResponse::from_board(
+      board,
+      &FirestoreReference(
+        firestore
+          .parent_path("participants", &participant.id)
+          .unwrap()
+          .into(),
+      ),
+    )),
+  )
+}
+
+// Join codes are easier to share than board IDs, but private boards still need an
+// invite to be joined.
+#[get("join/{join_code}")]
+pub async fn join_by_code(
+  firestore: web::Data<FirestoreDb>,
+  guard: web::Data<JoinCodeGuard>,
+  participant: Participant,
+  join_code: web::Path<String>,
+  req: HttpRequest,
+) -> Result<HttpResponse, Error> {
+  let keys = [
+    format!("participant:{}", participant.id),
+    format!("address:{}", client_address(&req)),
+  ];
+  guard.check(&keys)?;
+  let board_id = match db::resolve_join_code(&firestore, &join_code).await {
+    Err(Error::NotFound) => {
+      guard.record_miss(&keys);
+      return Err(Error::NotFound);
+    }
+    board_id => board_id?,
+  };
+  let board = db::get(&firestore, &board_id).await?;
+  if board.private {
+    return Err(Error::Forbidden);
+  }
+  add_participant_board(&firestore, &participant, &board).await?;
+  Ok(
+    HttpResponse::Ok().json(BoardResponse::from_board(
+      board,
+      &FirestoreReference(
+        firestore
+          .parent_path("participants", &participant.id)
+          .unwrap()
+          .into(),
+      ),
+    )),
+  )
+}
+
+#[post("boards/{board_id}/join_code")]
+pub async fn regenerate_join_code(
+  firestore: web::Data<FirestoreDb>,
+  broker: web::Data<Broker>,
+  participant: Participant,
+  board_id: web::Path<String>,
+) -> Result<HttpResponse, Error> {
+  let board = super::assert_board_access(&firestore, &participant, &board_id).await?;
+  let participant_reference = FirestoreReference(
+    firestore
+      .parent_path("participants", &participant.id)
+      .unwrap()
+      .into(),
+  );
+  check_owner_permission(&board, &participant_reference)?;
+  let board = db::assign_join_code(&firestore, &board_id, board.join_code.as_ref()).await?;
+  broker.publish(&board_id, BoardEvent::BoardChanged);
+  Ok(HttpResponse::Ok().json(BoardResponse::from_board(board, &participant_reference)))
+}
+
+#[delete("boards/{board_id}/join_code")]
+pub async fn disable_join_code(
+  firestore: web::Data<FirestoreDb>,
+  broker: web::Data<Broker>,
+  participant: Participant,
+  board_id: web::Path<String>,
+) -> Result<HttpResponse, Error> {
+  let board = super::assert_board_access(&firestore, &participant, &board_id).await?;
+  let participant_reference = FirestoreReference(
+    firestore
+      .parent_path("participants", &participant.id)
+      .unwrap()
+      .into(),
+  );
+  check_owner_permission(&board, &participant_reference)?;
+  let join_code = board.join_code.ok_or(Error::NotFound)?;
+  let board = db::disable_join_code(&firestore, &board_id, &join_code).await?;
+  broker.publish(&board_id, BoardEvent::BoardChanged);
+  Ok(HttpResponse::Ok().json(BoardResponse::from_board(board, &participant_reference)))
+}
+
+#[post("boards/{board_id}/transfer")]
+pub async fn transfer(
+  firestore: web::Data<FirestoreDb>,
+  broker: web::Data<Broker>,
+  participant: Participant,
+  board_id: web::Path<String>,
+  transfer_message: web::Json<BoardTransferMessage>,
+) -> Result<HttpResponse, Error> {
+  let board = super::assert_board_access(&firestore, &participant, &board_id).await?;
+  let participant_reference = FirestoreReference(
+    firestore
+      .parent_path("participants", &participant.id)
+      .unwrap()
+      .into(),
+  );
+  check_owner_permission(&board, &participant_reference)?;
+  let new_owner_id = transfer_message.into_inner().participant_id.ok_or_else(|| {
+    Error::BadRequest("A participant to transfer the board to is required.".into())
+  })?;
+  if new_owner_id == participant.id {
+    return Err(Error::BadRequest("The board is already owned by this participant.".into()));
+  }
+  if !has_joined(&firestore, &new_owner_id, &board_id).await? {
+    return Err(Error::BadRequest("The new owner must have joined the board.".into()));
+  }
+  let new_owner_reference = FirestoreReference(
+    firestore
+      .parent_path("participants", &new_owner_id)
+      .unwrap()
+      .into(),
+  );
+  let board = db::transfer_ownership(
+    &firestore,
+    &board_id,
+    &participant_reference,
+    &new_owner_reference,
+  )
+  .await?;
+  broker.publish(&board_id, BoardEvent::BoardChanged);
+  Ok(HttpResponse::Ok().json(BoardResponse::from_board(board, &participant_reference)))
+}
+
+#[get("boards/{board_id}/facilitators")]
+pub async fn facilitators(
+  firestore: web::Data<FirestoreDb>,
+  participant: Participant,
+  board_id: web::Path<String>,
+) -> Result<HttpResponse, Error> {
+  let board = super::assert_board_access(&firestore, &participant, &board_id).await?;
+  let participant_reference = FirestoreReference(
+    firestore
+      .parent_path("participants", &participant.id)
+      .unwrap()
+      .into(),
+  );
+  check_facilitator_permission(&board, &participant_reference)?;
+  Ok(HttpResponse::Ok().json(FacilitatorsResponse::from_board(&board)))
+}
+
+#[get("boards/{board_id}/participants")]
+pub async fn participants(
+  firestore: web::Data<FirestoreDb>,
+  participant: Participant,
+  board_id: web::Path<String>,
+) -> Result<HttpResponse, Error> {
+  let board = super::assert_board_access(&firestore, &participant, &board_id).await?;
+  let participant_reference = FirestoreReference(
+    firestore
+      .parent_path("participants", &participant.id)
+      .unwrap()
+      .into(),
+  );
+  check_participants_permission(&board, &participant_reference)?;
+  let cards = cards::db::list(&firestore, &board_id).await?;
+  let members = list_board_members(&firestore, &board_id).await?;
+  Ok(
+    HttpResponse::Ok().json(
+      members
+        .into_iter()
+        .map(|member| MemberResponse::from_member(member, &board, &cards, &participant_reference))
+        .collect::<Vec<MemberResponse>>(),
+    ),
+  )
+}
+
+#[put("boards/{board_id}/facilitators/{participant_id}")]
+pub async fn add_facilitator(
+  firestore: web::Data<FirestoreDb>,
+  broker: web::Data<Broker>,
+  participant: Participant,
+  params: web::Path<(String, String)>,
+) -> Result<HttpResponse, Error> {
+  let (board_id, facilitator_id) = params.into_inner();
+  let board = super::assert_board_access(&firestore, &participant, &board_id).await?;
+  let participant_reference = FirestoreReference(
+    firestore
+      .parent_path("participants", &participant.id)
+      .unwrap()
+      .into(),
+  );
+  check_owner_permission(&board, &participant_reference)?;
+  if facilitator_id == participant.id {
+    return Err(Error::BadRequest(
+      "The owner can't also be a facilitator.".into(),
+    ));
+  }
+  if !has_joined(&firestore, &facilitator_id, &board_id).await? {
+    return Err(Error::BadRequest(
+      "Facilitators must have joined the board.".into(),
+    ));
+  }
+  let facilitator_reference = FirestoreReference(
+    firestore
+      .parent_path("participants", &facilitator_id)
+      .unwrap()
+      .into(),
+  );
+  let board = db::add_facilitator(&firestore, &board_id, &facilitator_reference).await?;
+  broker.publish(&board_id, BoardEvent::BoardChanged);
+  Ok(HttpResponse::Ok().json(FacilitatorsResponse::from_board(&board)))
+}
+
+#[delete("boards/{board_id}/facilitators/{participant_id}")]
+pub async fn remove_facilitator(
+  firestore: web::Data<FirestoreDb>,
+  broker: web::Data<Broker>,
+  participant: Participant,
+  params: web::Path<(String, String)>,
+) -> Result<HttpResponse, Error> {
+  let (board_id, facilitator_id) = params.into_inner();
+  let board = super::assert_board_access(&firestore, &participant, &board_id).await?;
+  let participant_reference = FirestoreReference(
+    firestore
+      .parent_path("participants", &participant.id)
+      .unwrap()
+      .into(),
+  );
+  check_owner_permission(&board, &participant_reference)?;
+  let facilitator_reference = FirestoreReference(
+    firestore
+      .parent_path("participants", &facilitator_id)
+      .unwrap()
+      .into(),
+  );
+  if !board.facilitators.contains(&facilitator_reference) {
+    return Err(Error::NotFound);
+  }
+  let board = db::remove_facilitator(&firestore, &board_id, &facilitator_reference).await?;
+  broker.publish(&board_id, BoardEvent::BoardChanged);
+  Ok(HttpResponse::Ok().json(FacilitatorsResponse::from_board(&board)))
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -166,6 +641,16 @@ mod tests {
       owner: ref_(owner),
       open_permission,
       data: serde_json::Value::Object(Map::new()),
+      max_votes_per_participant: None,
+      multi_vote: false,
+      blind_voting: false,
+      anonymous: false,
+      private: false,
+      join_code: None,
+      deleted_at: None,
+      expire_at: None,
+      facilitators: vec![],
+      ownership_transfers: vec![],
     }
   }
 
@@ -177,25 +662,26 @@ mod tests {
       ice_breaking: None,
       data: None,
       open_permission,
+      ..Default::default()
     }
   }
 
   #[test]
   fn owner_can_delete() {
     let board = make_board("participants/owner", false);
-    assert!(check_delete_permission(&board, &ref_("participants/owner")).is_ok());
+    assert!(check_owner_permission(&board, &ref_("participants/owner")).is_ok());
   }
 
   #[test]
   fn non_owner_cannot_delete() {
     let board = make_board("participants/owner", false);
-    assert!(check_delete_permission(&board, &ref_("participants/other")).is_err());
+    assert!(check_owner_permission(&board, &ref_("participants/other")).is_err());
   }
 
   #[test]
   fn non_owner_cannot_delete_even_when_open_permission() {
     let board = make_board("participants/owner", true);
-    assert!(check_delete_permission(&board, &ref_("participants/other")).is_err());
+    assert!(check_owner_permission(&board, &ref_("participants/other")).is_err());
   }
 
   #[test]
@@ -247,4 +733,103 @@ mod tests {
       check_update_permission(&board, &ref_("participants/owner"), &msg(Some(false))).is_ok()
     );
   }
+
+  #[test]
+  fn facilitator_can_update_and_toggle_open_permission() {
+    let mut board = make_board("participants/owner", false);
+    board.facilitators = vec![ref_("participants/facilitator")];
+    let facilitator = ref_("participants/facilitator");
+    assert!(check_update_permission(&board, &facilitator, &msg(None)).is_ok());
+    assert!(check_update_permission(&board, &facilitator, &msg(Some(true))).is_ok());
+  }
+
+  #[test]
+  fn facilitator_cannot_delete_or_manage_roles() {
+    let mut board = make_board("participants/owner", false);
+    board.facilitators = vec![ref_("participants/facilitator")];
+    assert!(check_facilitator_permission(&board, &ref_("participants/facilitator")).is_ok());
+    assert!(check_owner_permission(&board, &ref_("participants/facilitator")).is_err());
+  }
+
+  #[test]
+  fn participant_is_not_a_facilitator() {
+    let board = make_board("participants/owner", true);
+    assert!(check_facilitator_permission(&board, &ref_("participants/other")).is_err());
+    assert!(check_facilitator_permission(&board, &ref_("participants/owner")).is_ok());
+  }
+
+  #[test]
+  fn only_owner_can_make_board_private() {
+    let mut board = make_board("participants/owner", true);
+    board.facilitators = vec![ref_("participants/facilitator")];
+    let private = BoardMessage {
+      private: Some(true),
+      ..Default::default()
+    };
+    assert!(check_update_permission(&board, &ref_("participants/owner"), &private).is_ok());
+    assert!(check_update_permission(&board, &ref_("participants/facilitator"), &private).is_err());
+    assert!(check_update_permission(&board, &ref_("participants/other"), &private).is_err());
+  }
+
+  #[test]
+  fn only_facilitators_can_toggle_anonymity() {
+    let mut board = make_board("participants/owner", true);
+    board.anonymous = true;
+    board.facilitators = vec![ref_("participants/facilitator")];
+    let reveal = BoardMessage {
+      anonymous: Some(false),
+      ..Default::default()
+    };
+    assert!(check_update_permission(&board, &ref_("participants/owner"), &reveal).is_ok());
+    assert!(check_update_permission(&board, &ref_("participants/facilitator"), &reveal).is_ok());
+    assert!(check_update_permission(&board, &ref_("participants/other"), &reveal).is_err());
+  }
+
+  #[test]
+  fn only_facilitators_can_change_voting_rules() {
+    let mut board = make_board("participants/owner", true);
+    board.facilitators = vec![ref_("participants/facilitator")];
+    let changes = [
+      BoardMessage {
+        blind_voting: Some(false),
+        ..Default::default()
+      },
+      BoardMessage {
+        multi_vote: Some(true),
+        ..Default::default()
+      },
+      BoardMessage {
+        max_votes_per_participant: Some(0),
+        ..Default::default()
+      },
+    ];
+    for change in &changes {
+      assert!(check_update_permission(&board, &ref_("participants/facilitator"), change).is_ok());
+      assert!(check_update_permission(&board, &ref_("participants/other"), change).is_err());
+    }
+  }
+
+  #[test]
+  fn only_facilitators_can_close_blind_voting() {
+    let mut board = make_board("participants/owner", true);
+    let close = BoardMessage {
+      voting_open: Some(false),
+      ..Default::default()
+    };
+    assert!(check_update_permission(&board, &ref_("participants/other"), &close).is_ok());
+    board.blind_voting = true;
+    assert!(check_update_permission(&board, &ref_("participants/other"), &close).is_err());
+    assert!(check_update_permission(&board, &ref_("participants/owner"), &close).is_ok());
+  }
+
+  #[test]
+  fn participants_are_listed_for_facilitators_or_open_boards() {
+    let mut board = make_board("participants/owner", false);
+    board.facilitators = vec![ref_("participants/facilitator")];
+    assert!(check_participants_permission(&board, &ref_("participants/owner")).is_ok());
+    assert!(check_participants_permission(&board, &ref_("participants/facilitator")).is_ok());
+    assert!(check_participants_permission(&board, &ref_("participants/other")).is_err());
+    board.open_permission = true;
+    assert!(check_participants_permission(&board, &ref_("participants/other")).is_ok());
+  }
 }
@@ -7,8 +7,10 @@ use futures::StreamExt;
 use std::convert::TryInto;
 
 use super::models::*;
+use crate::boards;
 use crate::error::Error;
 use crate::participants::models::Participant;
+use crate::transactions;
 
 pub async fn new(
   firestore: &FirestoreDb,
@@ -52,6 +54,32 @@ pub async fn list(firestore: &FirestoreDb, board_id: &String) -> Result<Vec<Card
   Ok(cards)
 }
 
+pub async fn list_children(
+  firestore: &FirestoreDb,
+  board_id: &String,
+  card_id: &String,
+) -> Result<Vec<Card>, Error> {
+  let parent = FirestoreReference(format!(
+    "{}/cards/{}",
+    firestore.parent_path("boards", board_id)?,
+    card_id
+  ));
+  Ok(
+    firestore
+      .fluent()
+      .select()
+      .from("cards")
+      .parent(firestore.parent_path("boards", board_id)?)
+      .filter(|q| q.for_all([q.field(path!(CardInFirestore::parent)).eq(&parent)]))
+      .obj::<CardInFirestore>()
+      .query()
+      .await?
+      .into_iter()
+      .map(|card| card.into())
+      .collect(),
+  )
+}
+
 pub async fn get(
   firestore: &FirestoreDb,
   board_id: &String,
@@ -121,35 +149,106 @@ pub async fn delete(
     .map_err(|e| e.into())
 }
 
-pub async fn put_vote(
+// Rejects a vote once the participant has spent the board's budget.
+// A single vote repeated on `card_id` is free, as it changes nothing.
+async fn assert_votes_remaining(
   firestore: &FirestoreDb,
   participant: &Participant,
   board_id: &String,
-  card_id: &String,
+  card_id: Option<&String>,
+) -> Result<(), Error> {
+  let board = boards::db::get(firestore, board_id).await?;
+  let Some(max_votes) = board.max_votes_per_participant else {
+    return Ok(());
+  };
+  let participant_reference = FirestoreReference(
+    firestore
+      .parent_path("participants", &participant.id)
+      .unwrap()
+      .into(),
+  );
+  let cards = list(firestore, board_id).await?;
+  let already_voted = card_id.is_some_and(|card_id| {
+    cards
+      .iter()
+      .any(|card| card.id == *card_id && card.votes.contains(&participant_reference.0))
+  });
+  if !already_voted && votes_cast(&cards, &participant_reference) >= max_votes {
+    return Err(Error::BadRequest("No votes remaining.".into()));
+  }
+  Ok(())
+}
+
+pub async fn set_parent(
+  firestore: &FirestoreDb,
+  board_id: &String,
+  card_ids: &[String],
+  parent_id: Option<&String>,
 ) -> Result<(), Error> {
+  let change_set = CardParentChangeSet {
+    parent: parent_id.map(|parent_id| {
+      FirestoreReference(format!(
+        "{}/cards/{}",
+        firestore.parent_path("boards", board_id).unwrap(),
+        parent_id
+      ))
+    }),
+  };
   let mut transaction = firestore.begin_transaction().await?;
-  firestore
-    .fluent()
-    .update()
-    .in_col("cards")
-    .document_id(card_id)
-    .parent(firestore.parent_path("boards", board_id)?)
-    .transforms(|t| {
-      t.fields([t
-        .field(path!(CardInFirestore::votes))
-        .append_missing_elements([FirestoreReference(
-          firestore
-            .parent_path("participants", &participant.id)
-            .unwrap()
-            .into(),
-        )])])
-    })
-    .only_transform()
-    .add_to_transaction(&mut transaction)?;
+  for card_id in card_ids {
+    firestore
+      .fluent()
+      .update()
+      .fields(paths!(CardInFirestore::parent))
+      .in_col("cards")
+      .document_id(card_id)
+      .parent(firestore.parent_path("boards", board_id)?)
+      .object(&change_set)
+      .add_to_transaction(&mut transaction)?;
+  }
   transaction.commit().await?;
   Ok(())
 }
 
+// The budget is read inside the transaction, so concurrent votes that would overspend
+// it together can't both commit.
+pub async fn put_vote(
+  firestore: &FirestoreDb,
+  participant: &Participant,
+  board_id: &String,
+  card_id: &String,
+) -> Result<(), Error> {
+  for _ in 0..transactions::ATTEMPTS {
+    let mut transaction = firestore.begin_transaction().await?;
+    let reads = transactions::reads(firestore, &transaction);
+    if let Err(e) = assert_votes_remaining(&reads, participant, board_id, Some(card_id)).await {
+      return transactions::abandon(transaction, Err(e)).await;
+    }
+    firestore
+      .fluent()
+      .update()
+      .in_col("cards")
+      .document_id(card_id)
+      .parent(firestore.parent_path("boards", board_id)?)
+      .transforms(|t| {
+        t.fields([t
+          .field(path!(CardInFirestore::votes))
+          .append_missing_elements([FirestoreReference(
+            firestore
+              .parent_path("participants", &participant.id)
+              .unwrap()
+              .into(),
+          )])])
+      })
+      .only_transform()
+      .add_to_transaction(&mut transaction)?;
+    if transactions::commit(transaction).await? {
+      return Ok(());
+    }
+  }
+  Err(transactions::contended())
+}
+
 pub async fn delete_vote(
   firestore: &FirestoreDb,
   participant: &Participant,
@@ -179,6 +278,81 @@ pub async fn delete_vote(
   Ok(())
 }
 
+pub async fn increment_vote(
+  firestore: &FirestoreDb,
+  participant: &Participant,
+  board_id: &String,
+  card_id: &String,
+) -> Result<(), Error> {
+  for _ in 0..transactions::ATTEMPTS {
+    let mut transaction = firestore.begin_transaction().await?;
+    let reads = transactions::reads(firestore, &transaction);
+    // Make sure the card exists, otherwise the transform would create it
+    let checked = match get(&reads, board_id, card_id).await {
+      Ok(_) => assert_votes_remaining(&reads, participant, board_id, None).await,
+      Err(e) => Err(e),
+    };
+    if let Err(e) = checked {
+      return transactions::abandon(transaction, Err(e)).await;
+    }
+    firestore
+      .fluent()
+      .update()
+      .in_col("cards")
+      .document_id(card_id)
+      .parent(firestore.parent_path("boards", board_id)?)
+      .transforms(|t| {
+        t.fields([t
+          .field(format!("vote_counts.`{}`", participant.id))
+          .increment(1)])
+      })
+      .only_transform()
+      .add_to_transaction(&mut transaction)?;
+    if transactions::commit(transaction).await? {
+      return Ok(());
+    }
+  }
+  Err(transactions::contended())
+}
+
+// The count is read inside the transaction, so concurrent decrements can't take it
+// below zero.
+pub async fn decrement_vote(
+  firestore: &FirestoreDb,
+  participant: &Participant,
+  board_id: &String,
+  card_id: &String,
+) -> Result<(), Error> {
+  for _ in 0..transactions::ATTEMPTS {
+    let mut transaction = firestore.begin_transaction().await?;
+    let reads = transactions::reads(firestore, &transaction);
+    let card = match get(&reads, board_id, card_id).await {
+      Ok(card) => card,
+      Err(e) => return transactions::abandon(transaction, Err(e)).await,
+    };
+    if card.vote_counts.get(&participant.id).copied().unwrap_or(0) == 0 {
+      return transactions::abandon(transaction, Ok(())).await;
+    }
+    firestore
+      .fluent()
+      .update()
+      .in_col("cards")
+      .document_id(card_id)
+      .parent(firestore.parent_path("boards", board_id)?)
+      .transforms(|t| {
+        t.fields([t
+          .field(format!("vote_counts.`{}`", participant.id))
+          .increment(-1)])
+      })
+      .only_transform()
+      .add_to_transaction(&mut transaction)?;
+    if transactions::commit(transaction).await? {
+      return Ok(());
+    }
+  }
+  Err(transactions::contended())
+}
+
 pub async fn put_reaction(
   firestore: &FirestoreDb,
   participant: &Participant,
@@ -264,7 +438,6 @@ pub async fn delete_reaction(
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::boards;
   use crate::boards::models::BoardMessage;
   use crate::participants::models::Participant;
 
@@ -306,7 +479,9 @@ mod tests {
         ice_breaking: None,
         data: None,
         open_permission: None,
+        ..Default::default()
       },
+      chrono::Duration::days(180),
     )
     .await
     .unwrap()
@@ -375,6 +550,90 @@ mod tests {
     boards::db::delete(&db, &board_id).await.unwrap();
   }
 
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn put_vote_rejects_votes_beyond_budget() {
+    let db = emulator_db().await;
+    let participant = test_participant();
+    let board_id = boards::db::new(
+      &db,
+      &participant,
+      BoardMessage {
+        max_votes_per_participant: Some(1),
+        ..Default::default()
+      },
+      chrono::Duration::days(180),
+    )
+    .await
+    .unwrap()
+    .id;
+    let column_ref = format!("{}/boards/{}/columns/col1", db.get_documents_path(), board_id);
+    let first = new(&db, &participant, &board_id, card_msg(&column_ref)).await.unwrap();
+    let second = new(&db, &participant, &board_id, card_msg(&column_ref)).await.unwrap();
+    put_vote(&db, &participant, &board_id, &first.id).await.unwrap();
+    // Re-voting the same card doesn't spend any more of the budget.
+    put_vote(&db, &participant, &board_id, &first.id).await.unwrap();
+    let result = put_vote(&db, &participant, &board_id, &second.id).await;
+    assert!(matches!(result, Err(crate::error::Error::BadRequest(_))));
+    boards::db::delete(&db, &board_id).await.unwrap();
+  }
+
+  async fn setup_budgeted_board(db: &FirestoreDb, max_votes: u32) -> String {
+    boards::db::new(
+      db,
+      &test_participant(),
+      BoardMessage {
+        max_votes_per_participant: Some(max_votes),
+        ..Default::default()
+      },
+      chrono::Duration::days(180),
+    )
+    .await
+    .unwrap()
+    .id
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn concurrent_votes_cannot_overspend_the_budget() {
+    let db = emulator_db().await;
+    let participant = test_participant();
+    let board_id = setup_budgeted_board(&db, 1).await;
+    let column_ref = format!("{}/boards/{}/columns/col1", db.get_documents_path(), board_id);
+    let first = new(&db, &participant, &board_id, card_msg(&column_ref)).await.unwrap();
+    let second = new(&db, &participant, &board_id, card_msg(&column_ref)).await.unwrap();
+    let (a, b) = futures::join!(
+      put_vote(&db, &participant, &board_id, &first.id),
+      put_vote(&db, &participant, &board_id, &second.id)
+    );
+    assert_eq!([a.is_ok(), b.is_ok()].iter().filter(|ok| **ok).count(), 1);
+    let cards = list(&db, &board_id).await.unwrap();
+    let participant_reference =
+      FirestoreReference(format!("{}/participants/{}", db.get_documents_path(), participant.id));
+    assert_eq!(votes_cast(&cards, &participant_reference), 1);
+    boards::db::delete(&db, &board_id).await.unwrap();
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn concurrent_decrements_stop_at_zero() {
+    let db = emulator_db().await;
+    let participant = test_participant();
+    let board_id = setup_budgeted_board(&db, 3).await;
+    let column_ref = format!("{}/boards/{}/columns/col1", db.get_documents_path(), board_id);
+    let card = new(&db, &participant, &board_id, card_msg(&column_ref)).await.unwrap();
+    increment_vote(&db, &participant, &board_id, &card.id).await.unwrap();
+    let (a, b) = futures::join!(
+      decrement_vote(&db, &participant, &board_id, &card.id),
+      decrement_vote(&db, &participant, &board_id, &card.id)
+    );
+    a.unwrap();
+    b.unwrap();
+    let after = get(&db, &board_id, &card.id).await.unwrap();
+    assert_eq!(after.vote_counts.get(&participant.id).copied().unwrap_or(0), 0);
+    boards::db::delete(&db, &board_id).await.unwrap();
+  }
+
   #[tokio::test]
   #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
   async fn delete_vote_removes_participant_from_votes() {
@@ -390,6 +649,97 @@ mod tests {
     boards::db::delete(&db, &board_id).await.unwrap();
   }
 
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn set_parent_attaches_and_detaches_cards() {
+    let db = emulator_db().await;
+    let participant = test_participant();
+    let board_id = setup_board(&db).await;
+    let column_ref = format!("{}/boards/{}/columns/col1", db.get_documents_path(), board_id);
+    let parent = new(&db, &participant, &board_id, card_msg(&column_ref)).await.unwrap();
+    let child = new(&db, &participant, &board_id, card_msg(&column_ref)).await.unwrap();
+    set_parent(&db, &board_id, std::slice::from_ref(&child.id), Some(&parent.id)).await.unwrap();
+    let grouped = get(&db, &board_id, &child.id).await.unwrap();
+    assert_eq!(grouped.parent_id(), Some(parent.id.clone()));
+    let children = list_children(&db, &board_id, &parent.id).await.unwrap();
+    assert_eq!(children.iter().map(|c| &c.id).collect::<Vec<_>>(), [&child.id]);
+    set_parent(&db, &board_id, std::slice::from_ref(&child.id), None).await.unwrap();
+    assert!(list_children(&db, &board_id, &parent.id).await.unwrap().is_empty());
+    let ungrouped = get(&db, &board_id, &child.id).await.unwrap();
+    assert!(ungrouped.parent.is_none());
+    assert_eq!(ungrouped.text, "Test card text");
+    boards::db::delete(&db, &board_id).await.unwrap();
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn increment_and_decrement_vote_stack_per_participant() {
+    let db = emulator_db().await;
+    let participant = test_participant();
+    let board_id = setup_board(&db).await;
+    let column_ref = format!("{}/boards/{}/columns/col1", db.get_documents_path(), board_id);
+    let card = new(&db, &participant, &board_id, card_msg(&column_ref)).await.unwrap();
+    increment_vote(&db, &participant, &board_id, &card.id).await.unwrap();
+    increment_vote(&db, &participant, &board_id, &card.id).await.unwrap();
+    increment_vote(&db, &participant, &board_id, &card.id).await.unwrap();
+    decrement_vote(&db, &participant, &board_id, &card.id).await.unwrap();
+    let after = get(&db, &board_id, &card.id).await.unwrap();
+    assert_eq!(after.vote_counts[&participant.id], 2);
+    assert_eq!(after.vote_total(), 2);
+    boards::db::delete(&db, &board_id).await.unwrap();
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn decrement_vote_never_goes_below_zero() {
+    let db = emulator_db().await;
+    let participant = test_participant();
+    let board_id = setup_board(&db).await;
+    let column_ref = format!("{}/boards/{}/columns/col1", db.get_documents_path(), board_id);
+    let card = new(&db, &participant, &board_id, card_msg(&column_ref)).await.unwrap();
+    decrement_vote(&db, &participant, &board_id, &card.id).await.unwrap();
+    let after = get(&db, &board_id, &card.id).await.unwrap();
+    assert!(after.vote_counts.is_empty());
+    boards::db::delete(&db, &board_id).await.unwrap();
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn increment_vote_respects_budget() {
+    let db = emulator_db().await;
+    let participant = test_participant();
+    let board_id = boards::db::new(
+      &db,
+      &participant,
+      BoardMessage {
+        multi_vote: Some(true),
+        max_votes_per_participant: Some(2),
+        ..Default::default()
+      },
+      chrono::Duration::days(180),
+    )
+    .await
+    .unwrap()
+    .id;
+    let column_ref = format!("{}/boards/{}/columns/col1", db.get_documents_path(), board_id);
+    let card = new(&db, &participant, &board_id, card_msg(&column_ref)).await.unwrap();
+    increment_vote(&db, &participant, &board_id, &card.id).await.unwrap();
+    increment_vote(&db, &participant, &board_id, &card.id).await.unwrap();
+    let result = increment_vote(&db, &participant, &board_id, &card.id).await;
+    assert!(matches!(result, Err(crate::error::Error::BadRequest(_))));
+    boards::db::delete(&db, &board_id).await.unwrap();
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn increment_vote_on_missing_card_is_not_found() {
+    let db = emulator_db().await;
+    let board_id = setup_board(&db).await;
+    let result = increment_vote(&db, &test_participant(), &board_id, &"no-such-card".to_string()).await;
+    assert!(matches!(result, Err(crate::error::Error::NotFound)));
+    boards::db::delete(&db, &board_id).await.unwrap();
+  }
+
   #[tokio::test]
   #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
   async fn put_reaction_records_emoji_for_participant() {
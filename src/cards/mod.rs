@@ -1,13 +1,23 @@
-mod db;
+pub mod db;
 pub mod models;
 pub mod routes;
 
 use firestore::{FirestoreDb, FirestoreReference};
+use std::collections::HashMap;
 
 use crate::boards;
+use crate::boards::models::Board;
 use crate::error::Error;
 use crate::participants::models::Participant;
 
+fn validate_card_text(card_message: &models::CardMessage) -> Result<(), Error> {
+  match &card_message.text {
+    Some(text) if text.is_empty() => Err(Error::BadRequest("Empty cards are not allowed.".into())),
+    None => Err(Error::BadRequest("Card text must be provided.".into())),
+    Some(_) => Ok(()),
+  }
+}
+
 pub async fn assert_card_owner(
   firestore: &FirestoreDb,
   participant: &Participant,
@@ -20,9 +30,157 @@ pub async fn assert_card_owner(
     firestore.get_documents_path(),
     participant.id
   ));
-  if board.owner == participant_reference || card.owner == participant_reference || board.open_permission {
+  check_card_owner(&board, &participant_reference, card)
+}
+
+fn check_card_owner(
+  board: &Board,
+  participant_reference: &FirestoreReference,
+  card: &models::Card,
+) -> Result<(), Error> {
+  if board.facilitated_by(participant_reference)
+    || card.owner == *participant_reference
+    || board.open_permission
+  {
     Ok(())
   } else {
     Err(Error::Forbidden)
   }
 }
+
+pub async fn new_card(
+  firestore: &FirestoreDb,
+  participant: &Participant,
+  board_id: &String,
+  column_id: &String,
+  mut card_message: models::CardMessage,
+) -> Result<models::Card, Error> {
+  boards::assert_cards_allowed(firestore, board_id).await?;
+  card_message.author.get_or_insert("".into());
+  card_message.column = Some(format!(
+    "{}/columns/{}",
+    firestore.parent_path("boards", board_id)?,
+    column_id
+  ));
+
+  validate_card_text(&card_message)?;
+
+  db::new(firestore, participant, board_id, card_message).await
+}
+
+pub async fn update_card(
+  firestore: &FirestoreDb,
+  participant: &Participant,
+  board_id: &String,
+  card_id: &String,
+  card_message: models::CardMessage,
+) -> Result<models::Card, Error> {
+  let card = db::get(firestore, board_id, card_id).await?;
+  assert_card_owner(firestore, participant, &card, board_id).await?;
+  db::update(firestore, board_id, card_id, card_message).await
+}
+
+// Groups are one level deep, so cards already parenting a group bring their
+// children along to the new parent. Returns every card that was moved.
+pub async fn group_cards(
+  firestore: &FirestoreDb,
+  participant: &Participant,
+  board_id: &String,
+  parent_id: &String,
+  card_ids: Vec<String>,
+) -> Result<Vec<models::Card>, Error> {
+  let board = boards::db::get(firestore, board_id).await?;
+  let participant_reference = FirestoreReference(format!(
+    "{}/participants/{}",
+    firestore.get_documents_path(),
+    participant.id
+  ));
+  let parent = db::get(firestore, board_id, parent_id).await?;
+  if parent.parent_id().is_some() {
+    return Err(Error::BadRequest("A grouped card can't parent another group.".into()));
+  }
+  check_card_owner(&board, &participant_reference, &parent)?;
+
+  let mut moved: HashMap<String, models::Card> = HashMap::new();
+  for card_id in card_ids {
+    if card_id == *parent_id {
+      return Err(Error::BadRequest("A card can't be grouped under itself.".into()));
+    }
+    let card = db::get(firestore, board_id, &card_id).await?;
+    check_card_owner(&board, &participant_reference, &card)?;
+    for child in db::list_children(firestore, board_id, &card_id).await? {
+      moved.insert(child.id.clone(), child);
+    }
+    moved.insert(card_id, card);
+  }
+  let mut moved_ids: Vec<String> = moved.keys().cloned().collect();
+  moved_ids.sort();
+
+  db::set_parent(firestore, board_id, &moved_ids, Some(parent_id)).await?;
+  Ok(
+    moved_ids
+      .iter()
+      .filter_map(|card_id| moved.remove(card_id))
+      .collect(),
+  )
+}
+
+// Ungrouping a child detaches just that card, while ungrouping a parent
+// dissolves the whole group. Returns every card that was detached.
+pub async fn ungroup_card(
+  firestore: &FirestoreDb,
+  participant: &Participant,
+  board_id: &String,
+  card_id: &String,
+) -> Result<Vec<models::Card>, Error> {
+  let card = db::get(firestore, board_id, card_id).await?;
+  assert_card_owner(firestore, participant, &card, board_id).await?;
+  let detached: Vec<models::Card> = match card.parent_id() {
+    Some(_) => vec![card],
+    None => db::list_children(firestore, board_id, card_id).await?,
+  };
+  let detached_ids: Vec<String> = detached.iter().map(|card| card.id.clone()).collect();
+  db::set_parent(firestore, board_id, &detached_ids, None).await?;
+  Ok(detached)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use models::CardMessage;
+
+  fn msg(text: Option<&str>) -> CardMessage {
+    CardMessage { author: None, text: text.map(|s| s.to_string()), column: None }
+  }
+
+  #[test]
+  fn non_empty_text_is_valid() {
+    assert!(validate_card_text(&msg(Some("Hello"))).is_ok());
+  }
+
+  #[test]
+  fn empty_text_is_bad_request() {
+    assert!(matches!(validate_card_text(&msg(Some(""))), Err(Error::BadRequest(_))));
+  }
+
+  #[test]
+  fn empty_text_error_message() {
+    match validate_card_text(&msg(Some(""))) {
+      Err(Error::BadRequest(s)) => assert_eq!(s, "Empty cards are not allowed."),
+      other => panic!("expected BadRequest, got {other:?}"),
+    }
+  }
+
+  #[test]
+  fn missing_text_is_bad_request() {
+    assert!(matches!(validate_card_text(&msg(None)), Err(Error::BadRequest(_))));
+  }
+
+  #[test]
+  fn missing_text_error_message() {
+    match validate_card_text(&msg(None)) {
+      Err(Error::BadRequest(s)) => assert_eq!(s, "Card text must be provided."),
+      other => panic!("expected BadRequest, got {other:?}"),
+    }
+  }
+}
@@ -1,7 +1,7 @@
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use firestore::{FirestoreReference, FirestoreTimestamp};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
 
 use crate::columns::models::Column;
@@ -29,6 +29,16 @@ pub struct ReactMessage {
   pub emoji: String,
 }
 
+#[derive(Deserialize, Serialize)]
+pub struct GroupMessage {
+  pub cards: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct CardParentChangeSet {
+  pub parent: Option<FirestoreReference>,
+}
+
 #[derive(Deserialize, Serialize)]
 pub struct Card {
   pub id: String,
@@ -38,7 +48,9 @@ pub struct Card {
   pub text: String,
   pub created_at: i64,
   pub votes: Vec<String>,
+  pub vote_counts: HashMap<String, u32>,
   pub reactions: HashMap<String, Vec<String>>,
+  pub parent: Option<FirestoreReference>,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -51,8 +63,19 @@ pub struct CardResponse {
   pub created_at: i64,
   pub votes: usize,
   pub voted: bool,
+  pub my_votes: u32,
+  pub votes_hidden: bool,
   pub reactions: HashMap<String, usize>,
   pub reacted: String,
+  pub parent: Option<String>,
+  pub children: Vec<CardResponse>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct VotesResponse {
+  pub max_votes: Option<u32>,
+  pub votes_cast: u32,
+  pub votes_remaining: Option<u32>,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -64,6 +87,20 @@ pub struct NewCard {
   pub text: String,
 }
 
+// A card copied onto a cloned board, keeping its author and creation time.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct ClonedCard {
+  pub created_at: FirestoreTimestamp,
+  pub column: FirestoreReference,
+  pub owner: FirestoreReference,
+  pub author: String,
+  pub text: String,
+  pub votes: Vec<String>,
+  pub vote_counts: HashMap<String, u32>,
+  pub reactions: HashMap<String, Vec<String>>,
+  pub parent: Option<FirestoreReference>,
+}
+
 #[derive(Deserialize, Serialize)]
 pub struct CardInFirestore {
   pub _firestore_id: String,
@@ -74,12 +111,17 @@ pub struct CardInFirestore {
   pub owner: FirestoreReference,
   pub column: FirestoreReference,
   pub votes: Option<Vec<String>>,
+  // Multi-vote counts keyed by participant ID, changed only through increments that
+  // are checked in the same transaction, so they never go below zero.
+  pub vote_counts: Option<HashMap<String, u32>>,
   pub reactions: Option<HashMap<String, Vec<String>>>,
+  pub parent: Option<FirestoreReference>,
 }
 
 #[derive(Serialize)]
 pub struct CardCSVRow {
   pub column: String,
+  pub group: String,
   pub author: String,
   pub text: String,
   pub created_at: i64,
@@ -118,7 +160,94 @@ impl From<CardInFirestore> for Card {
       author: card.author,
       text: card.text,
       votes: card.votes.unwrap_or_default(),
+      vote_counts: card.vote_counts.unwrap_or_default(),
       reactions: card.reactions.unwrap_or_default(),
+      parent: card.parent,
+    }
+  }
+}
+
+impl ClonedCard {
+  pub fn from_card(
+    card: Card,
+    column: FirestoreReference,
+    parent: Option<FirestoreReference>,
+    keep_votes: bool,
+  ) -> ClonedCard {
+    let (votes, vote_counts, reactions) = match keep_votes {
+      true => (card.votes, card.vote_counts, card.reactions),
+      false => Default::default(),
+    };
+    ClonedCard {
+      created_at: FirestoreTimestamp(
+        DateTime::from_timestamp(card.created_at, 0).unwrap_or_else(Utc::now),
+      ),
+      column,
+      owner: card.owner,
+      author: card.author,
+      text: card.text,
+      votes,
+      vote_counts,
+      reactions,
+      parent,
+    }
+  }
+}
+
+impl Card {
+  pub fn column_id(&self) -> String {
+    self.column.0.split('/').next_back().unwrap().to_string()
+  }
+
+  pub fn parent_id(&self) -> Option<String> {
+    self
+      .parent
+      .as_ref()
+      .map(|parent| parent.0.split('/').next_back().unwrap().to_string())
+  }
+
+  pub fn vote_total(&self) -> usize {
+    self.votes.len() + self.vote_counts.values().map(|count| *count as usize).sum::<usize>()
+  }
+
+  pub fn votes_by(&self, participant_id: &FirestoreReference) -> u32 {
+    let single = self.votes.contains(&participant_id.0) as u32;
+    let participant = participant_id.0.split('/').next_back().unwrap_or_default();
+    single + self.vote_counts.get(participant).copied().unwrap_or(0)
+  }
+}
+
+pub fn votes_cast(cards: &[Card], participant_id: &FirestoreReference) -> u32 {
+  cards.iter().map(|card| card.votes_by(participant_id)).sum()
+}
+
+// Maps each grouped card, parent included, to the text of the group's parent.
+pub fn group_names(cards: &[Card]) -> HashMap<String, String> {
+  let texts: HashMap<&String, &String> = cards.iter().map(|card| (&card.id, &card.text)).collect();
+  let mut groups = HashMap::new();
+  for card in cards {
+    let Some(parent_id) = card.parent_id() else {
+      continue;
+    };
+    if let Some(text) = texts.get(&parent_id) {
+      groups.insert(card.id.clone(), text.to_string());
+      groups.insert(parent_id, text.to_string());
+    }
+  }
+  groups
+}
+
+impl VotesResponse {
+  pub fn new(
+    max_votes: Option<u32>,
+    cards: &[Card],
+    participant_id: &FirestoreReference,
+  ) -> VotesResponse {
+    let votes_cast = votes_cast(cards, participant_id);
+    VotesResponse {
+      max_votes,
+      votes_cast,
+      votes_remaining: max_votes.map(|max| max.saturating_sub(votes_cast)),
     }
   }
 }
@@ -126,7 +255,7 @@ impl From<CardInFirestore> for Card {
 impl CardCSVRow {
   pub fn from_card(card: Card, columns: &HashMap<String, Column>) -> CardCSVRow {
     CardCSVRow {
-      column: match columns.get(&card.column.0.split('/').next_back().unwrap().to_string()) {
+      column: match columns.get(&card.column_id()) {
         Some(column) => column
           .name
           .clone()
@@ -136,10 +265,11 @@ impl CardCSVRow {
           .into(),
         _ => "Unknown Column".into(),
       },
+      votes: card.vote_total(),
+      group: "".into(),
       author: card.author,
       text: card.text,
       created_at: card.created_at,
-      votes: card.votes.len(),
     }
   }
 }
@@ -147,14 +277,18 @@ impl CardCSVRow {
 impl CardResponse {
   pub fn from_card(card: Card, participant_id: &FirestoreReference) -> CardResponse {
     CardResponse {
+      column: card.column_id(),
+      votes: card.vote_total(),
+      voted: card.votes_by(participant_id) > 0,
+      my_votes: card.votes_by(participant_id),
+      votes_hidden: false,
+      parent: card.parent_id(),
+      children: vec![],
       id: card.id,
-      column: card.column.0.split('/').next_back().unwrap().to_string(),
       owner: &card.owner == participant_id,
       author: card.author,
       text: card.text,
       created_at: card.created_at,
-      votes: card.votes.len(),
-      voted: card.votes.contains(&participant_id.0),
       reactions: card
         .reactions
         .clone()
@@ -176,6 +310,57 @@ impl CardResponse {
       },
     }
   }
+
+  // Nests grouped cards under their parent, folding their votes and reactions
+  // into the parent's totals. Cards whose parent has gone stay at the top level.
+  pub fn nest(responses: Vec<CardResponse>) -> Vec<CardResponse> {
+    let parents: HashSet<String> = responses
+      .iter()
+      .filter(|response| response.parent.is_none())
+      .map(|response| response.id.clone())
+      .collect();
+    let (children, mut top_level): (Vec<CardResponse>, Vec<CardResponse>) =
+      responses.into_iter().partition(|response| {
+        response
+          .parent
+          .as_ref()
+          .is_some_and(|parent| parents.contains(parent))
+      });
+    let mut by_parent: HashMap<String, Vec<CardResponse>> = HashMap::new();
+    for child in children {
+      by_parent
+        .entry(child.parent.clone().unwrap())
+        .or_default()
+        .push(child);
+    }
+    for response in top_level.iter_mut() {
+      if let Some(children) = by_parent.remove(&response.id) {
+        response.adopt(children);
+      }
+    }
+    top_level
+  }
+
+  fn adopt(&mut self, children: Vec<CardResponse>) {
+    for child in &children {
+      self.votes += child.votes;
+      self.my_votes += child.my_votes;
+      self.voted |= child.voted;
+      for (emoji, count) in &child.reactions {
+        *self.reactions.entry(emoji.clone()).or_default() += count;
+      }
+    }
+    self.children = children;
+  }
+
+  // Keeps the caller's own vote state while blind voting hides the totals.
+  pub fn hide_votes(self) -> CardResponse {
+    CardResponse {
+      votes: 0,
+      votes_hidden: true,
+      ..self
+    }
+  }
 }
 
 #[cfg(test)]
@@ -199,7 +384,9 @@ mod tests {
       text: "Test card".to_string(),
       created_at: 1_000_000,
       votes: vec![],
+      vote_counts: HashMap::new(),
       reactions: HashMap::new(),
+      parent: None,
     }
   }
 
@@ -259,13 +446,35 @@ mod tests {
       owner: ref_("participants/user1"),
       column: ref_("boards/b1/columns/col1"),
       votes: None,
+      vote_counts: None,
       reactions: None,
+      parent: None,
     };
     let card: Card = raw.into();
     assert!(card.votes.is_empty());
+    assert!(card.vote_counts.is_empty());
     assert!(card.reactions.is_empty());
   }
 
+  #[test]
+  fn card_in_firestore_keeps_vote_counts() {
+    let raw = CardInFirestore {
+      _firestore_id: "c1".into(),
+      _firestore_created: FirestoreTimestamp(Utc::now()),
+      created_at: None,
+      author: "Bob".into(),
+      text: "Hello".into(),
+      owner: ref_("participants/user1"),
+      column: ref_("boards/b1/columns/col1"),
+      votes: None,
+      vote_counts: Some(HashMap::from([("user1".into(), 2)])),
+      reactions: None,
+      parent: None,
+    };
+    let card: Card = raw.into();
+    assert_eq!(card.vote_counts["user1"], 2);
+  }
+
   // --- CardResponse ---
 
   #[test]
@@ -302,6 +511,40 @@ mod tests {
     assert_eq!(resp.votes, 1);
   }
 
+  #[test]
+  fn card_response_votes_reflect_weighted_counts() {
+    let participant = ref_("projects/p/databases/d/documents/participants/user1");
+    let mut card = make_card("c1", "participants/user1", "boards/b1/columns/col1");
+    card.vote_counts.insert("user1".into(), 3);
+    card.vote_counts.insert("user2".into(), 2);
+    let resp = CardResponse::from_card(card, &participant);
+    assert!(resp.voted);
+    assert_eq!(resp.my_votes, 3);
+    assert_eq!(resp.votes, 5);
+  }
+
+  #[test]
+  fn card_response_zero_vote_count_is_not_voted() {
+    let participant = ref_("participants/user1");
+    let mut card = make_card("c1", "participants/user2", "boards/b1/columns/col1");
+    card.vote_counts.insert("user1".into(), 0);
+    let resp = CardResponse::from_card(card, &participant);
+    assert!(!resp.voted);
+    assert_eq!(resp.votes, 0);
+  }
+
+  #[test]
+  fn card_response_hide_votes_keeps_own_vote_state() {
+    let participant = ref_("participants/user1");
+    let mut card = make_card("c1", "participants/user2", "boards/b1/columns/col1");
+    card.votes = vec!["participants/user1".into(), "participants/user2".into()];
+    let resp = CardResponse::from_card(card, &participant).hide_votes();
+    assert!(resp.votes_hidden);
+    assert_eq!(resp.votes, 0);
+    assert!(resp.voted);
+    assert_eq!(resp.my_votes, 1);
+  }
+
   #[test]
   fn card_response_reacted_returns_emoji_when_participant_reacted() {
     let participant = ref_("participants/user1");
@@ -342,6 +585,115 @@ mod tests {
     assert_eq!(resp.reactions["❤️"], 1);
   }
 
+  // --- Grouping ---
+
+  fn child_of(card: Card, parent: &str) -> Card {
+    Card {
+      parent: Some(ref_(&format!("boards/b1/cards/{parent}"))),
+      ..card
+    }
+  }
+
+  #[test]
+  fn card_parent_id_extracts_last_path_segment() {
+    let card = child_of(make_card("c2", "participants/user1", "boards/b1/columns/col1"), "c1");
+    assert_eq!(card.parent_id(), Some("c1".to_string()));
+  }
+
+  #[test]
+  fn nest_aggregates_votes_and_reactions_into_parent() {
+    let participant = ref_("participants/user1");
+    let mut parent = make_card("c1", "participants/user1", "boards/b1/columns/col1");
+    parent.votes = vec!["participants/user2".into()];
+    parent.reactions.insert("👍".into(), vec!["participants/user2".into()]);
+    let mut child = child_of(make_card("c2", "participants/user2", "boards/b1/columns/col1"), "c1");
+    child.votes = vec!["participants/user1".into(), "participants/user3".into()];
+    child.reactions.insert("👍".into(), vec!["participants/user3".into()]);
+    child.reactions.insert("🎉".into(), vec!["participants/user1".into()]);
+    let nested = CardResponse::nest(vec![
+      CardResponse::from_card(parent, &participant),
+      CardResponse::from_card(child, &participant),
+    ]);
+    assert_eq!(nested.len(), 1);
+    assert_eq!(nested[0].votes, 3);
+    assert!(nested[0].voted);
+    assert_eq!(nested[0].reactions["👍"], 2);
+    assert_eq!(nested[0].reactions["🎉"], 1);
+    assert_eq!(nested[0].children.len(), 1);
+    assert_eq!(nested[0].children[0].votes, 2);
+  }
+
+  #[test]
+  fn nest_keeps_cards_with_missing_parent_at_top_level() {
+    let participant = ref_("participants/user1");
+    let orphan = child_of(make_card("c2", "participants/user1", "boards/b1/columns/col1"), "gone");
+    let nested = CardResponse::nest(vec![CardResponse::from_card(orphan, &participant)]);
+    assert_eq!(nested.len(), 1);
+    assert_eq!(nested[0].parent, Some("gone".to_string()));
+  }
+
+  #[test]
+  fn group_names_label_parent_and_children_with_parent_text() {
+    let mut parent = make_card("c1", "participants/user1", "boards/b1/columns/col1");
+    parent.text = "Slow builds".into();
+    let child = child_of(make_card("c2", "participants/user1", "boards/b1/columns/col1"), "c1");
+    let loner = make_card("c3", "participants/user1", "boards/b1/columns/col1");
+    let groups = group_names(&[parent, child, loner]);
+    assert_eq!(groups["c1"], "Slow builds");
+    assert_eq!(groups["c2"], "Slow builds");
+    assert!(!groups.contains_key("c3"));
+  }
+
+  // --- Vote budget ---
+
+  #[test]
+  fn votes_cast_counts_cards_voted_by_participant() {
+    let participant = ref_("participants/user1");
+    let mut voted = make_card("c1", "participants/user2", "boards/b1/columns/col1");
+    voted.votes = vec!["participants/user1".into(), "participants/user2".into()];
+    let mut other = make_card("c2", "participants/user2", "boards/b1/columns/col1");
+    other.votes = vec!["participants/user2".into()];
+    let unvoted = make_card("c3", "participants/user2", "boards/b1/columns/col1");
+    assert_eq!(votes_cast(&[voted, other, unvoted], &participant), 1);
+  }
+
+  #[test]
+  fn votes_cast_includes_weighted_counts() {
+    let participant = ref_("participants/user1");
+    let mut stacked = make_card("c1", "participants/user2", "boards/b1/columns/col1");
+    stacked.vote_counts.insert("user1".into(), 3);
+    let mut single = make_card("c2", "participants/user2", "boards/b1/columns/col1");
+    single.votes = vec!["participants/user1".into()];
+    assert_eq!(votes_cast(&[stacked, single], &participant), 4);
+  }
+
+  #[test]
+  fn votes_response_reports_remaining_budget() {
+    let participant = ref_("participants/user1");
+    let mut card = make_card("c1", "participants/user2", "boards/b1/columns/col1");
+    card.votes = vec!["participants/user1".into()];
+    let resp = VotesResponse::new(Some(3), &[card], &participant);
+    assert_eq!(resp.votes_cast, 1);
+    assert_eq!(resp.votes_remaining, Some(2));
+  }
+
+  #[test]
+  fn votes_response_without_budget_has_no_remaining_count() {
+    let participant = ref_("participants/user1");
+    let resp = VotesResponse::new(None, &[], &participant);
+    assert_eq!(resp.votes_cast, 0);
+    assert!(resp.votes_remaining.is_none());
+  }
+
+  #[test]
+  fn votes_response_remaining_never_underflows() {
+    let participant = ref_("participants/user1");
+    let mut card = make_card("c1", "participants/user2", "boards/b1/columns/col1");
+    card.votes = vec!["participants/user1".into()];
+    let resp = VotesResponse::new(Some(0), &[card], &participant);
+    assert_eq!(resp.votes_remaining, Some(0));
+  }
+
   // --- CardCSVRow ---
 
   #[test]
@@ -375,6 +727,15 @@ mod tests {
     assert_eq!(CardCSVRow::from_card(card, &columns).votes, 3);
   }
 
+  #[test]
+  fn card_csv_row_votes_include_weighted_counts() {
+    let mut card = make_card("c1", "participants/user1", "boards/b1/columns/col1");
+    card.votes = vec!["u1".into()];
+    card.vote_counts.insert("u2".into(), 4);
+    let columns: HashMap<String, Column> = HashMap::new();
+    assert_eq!(CardCSVRow::from_card(card, &columns).votes, 5);
+  }
+
   // --- Column path edge cases ---
 
   #[test]
@@ -407,4 +768,39 @@ mod tests {
     let resp = CardResponse::from_card(card, &participant);
     assert_eq!(resp.reacted, "🎉");
   }
+
+  // --- ClonedCard ---
+
+  #[test]
+  fn cloned_card_points_at_new_column_and_drops_votes() {
+    let mut card = make_card("c1", "participants/user1", "boards/b1/columns/col1");
+    card.votes = vec!["participants/user2".into()];
+    card.vote_counts.insert("user2".into(), 2);
+    card.reactions.insert("🎉".into(), vec!["participants/user2".into()]);
+    let cloned = ClonedCard::from_card(card, ref_("boards/b2/columns/col9"), None, false);
+    assert_eq!(cloned.column, ref_("boards/b2/columns/col9"));
+    assert_eq!(cloned.owner, ref_("participants/user1"));
+    assert_eq!(cloned.created_at.0.timestamp(), 1_000_000);
+    assert!(cloned.votes.is_empty());
+    assert!(cloned.vote_counts.is_empty());
+    assert!(cloned.reactions.is_empty());
+  }
+
+  #[test]
+  fn cloned_card_keeps_votes_and_reactions_when_asked() {
+    let mut card = make_card("c1", "participants/user1", "boards/b1/columns/col1");
+    card.votes = vec!["participants/user2".into()];
+    card.vote_counts.insert("user2".into(), 2);
+    card.reactions.insert("🎉".into(), vec!["participants/user2".into()]);
+    let cloned = ClonedCard::from_card(
+      card,
+      ref_("boards/b2/columns/col9"),
+      Some(ref_("boards/b2/cards/c7")),
+      true,
+    );
+    assert_eq!(cloned.votes, vec!["participants/user2".to_string()]);
+    assert_eq!(cloned.vote_counts.get("user2"), Some(&2));
+    assert_eq!(cloned.reactions.len(), 1);
+    assert_eq!(cloned.parent, Some(ref_("boards/b2/cards/c7")));
+  }
 }
@@ -4,53 +4,73 @@ use actix_web::http::header::{
 };
 use actix_web::{delete, get, patch, post, put, web, HttpResponse};
 use firestore::{FirestoreDb, FirestoreReference};
+use std::collections::HashMap;
 
 use super::db;
 use super::models::*;
+use crate::action_items;
+use crate::action_items::models::ActionItemCSVRow;
+use crate::boards::models::Board;
 use crate::boards::*;
 use crate::columns::get_columns;
 use crate::error::Error;
+use crate::events::models::BoardEvent;
+use crate::events::Broker;
+use crate::participants::db::touch_member;
 use crate::participants::models::Participant;
 
-fn validate_card_text(card_message: &CardMessage) -> Result<(), Error> {
-  match &card_message.text {
-    Some(text) if text.is_empty() => Err(Error::BadRequest("Empty cards are not allowed.".into())),
-    None => Err(Error::BadRequest("Card text must be provided.".into())),
-    Some(_) => Ok(()),
+fn card_response(card: Card, board: &Board, participant_id: &FirestoreReference) -> CardResponse {
+  let hide_author = board.hides_author_from(&card.owner, participant_id);
+  let mut response = CardResponse::from_card(card, participant_id);
+  if hide_author {
+    response.author = "".into();
   }
+  match board.hides_votes_from(participant_id) {
+    true => response.hide_votes(),
+    false => response,
+  }
+}
+
+fn participant_reference(firestore: &FirestoreDb, participant: &Participant) -> FirestoreReference {
+  FirestoreReference(
+    firestore
+      .parent_path("participants", &participant.id)
+      .unwrap()
+      .into(),
+  )
 }
 
 #[post("boards/{board_id}/columns/{column_id}/cards")]
 pub async fn new(
   firestore: web::Data<FirestoreDb>,
+  broker: web::Data<Broker>,
   participant: Participant,
   params: web::Path<(String, String)>,
   card_message: web::Json<CardMessage>,
 ) -> Result<HttpResponse, Error> {
   let (board_id, column_id) = params.into_inner();
-  assert_cards_allowed(&firestore, &board_id).await?;
-  let mut card_message = card_message.into_inner();
-  card_message.author.get_or_insert("".into());
-  card_message.column = Some(format!(
-    "{}/columns/{}",
-    firestore.parent_path("boards", &board_id)?,
-    column_id
-  ));
-
-  validate_card_text(&card_message)?;
-
-  let card = db::new(&firestore, &participant, &board_id, card_message).await?;
-  Ok(
-    HttpResponse::Ok().json(CardResponse::from_card(
-      card,
-      &FirestoreReference(
-        firestore
-          .parent_path("participants", &participant.id)
-          .unwrap()
-          .into(),
-      ),
-    )),
+  let board = assert_board_access(&firestore, &participant, &board_id).await?;
+  let card = super::new_card(
+    &firestore,
+    &participant,
+    &board_id,
+    &column_id,
+    card_message.into_inner(),
   )
+  .await?;
+  touch_member(&firestore, &board_id, &participant).await?;
+  broker.publish(
+    &board_id,
+    BoardEvent::CardCreated {
+      card_id: card.id.clone(),
+      column_id,
+    },
+  );
+  Ok(HttpResponse::Ok().json(card_response(
+    card,
+    &board,
+    &participant_reference(&firestore, &participant),
+  )))
 }
 
 #[get("boards/{board_id}/cards")]
@@ -59,25 +79,19 @@ pub async fn list(
   participant: Participant,
   board_id: web::Path<String>,
 ) -> Result<HttpResponse, Error> {
+  // Unknown boards have always listed as empty rather than not found.
+  let board = match assert_board_access(&firestore, &participant, &board_id).await {
+    Err(Error::NotFound) => return Ok(HttpResponse::Ok().json(Vec::<CardResponse>::new())),
+    board => board?,
+  };
   let cards = db::list(&firestore, &board_id).await?;
-  Ok(
-    HttpResponse::Ok().json(
-      cards
-        .into_iter()
-        .map(|card| {
-          CardResponse::from_card(
-            card,
-            &FirestoreReference(
-              firestore
-                .parent_path("participants", &participant.id)
-                .unwrap()
-                .into(),
-            ),
-          )
-        })
-        .collect::<Vec<CardResponse>>(),
-    ),
-  )
+  let participant_id = participant_reference(&firestore, &participant);
+  Ok(HttpResponse::Ok().json(CardResponse::nest(
+    cards
+      .into_iter()
+      .map(|card| card_response(card, &board, &participant_id))
+      .collect(),
+  )))
 }
 
 #[get("boards/{board_id}/cards/{card_id}")]
@@ -87,91 +101,208 @@ pub async fn get(
   params: web::Path<(String, String)>,
 ) -> Result<HttpResponse, Error> {
   let (board_id, card_id) = params.into_inner();
+  let board = assert_board_access(&firestore, &participant, &board_id).await?;
   let card = db::get(&firestore, &board_id, &card_id).await?;
-  Ok(
-    HttpResponse::Ok().json(CardResponse::from_card(
-      card,
-      &FirestoreReference(
-        firestore
-          .parent_path("participants", &participant.id)
-          .unwrap()
-          .into(),
-      ),
-    )),
-  )
+  let participant_id = participant_reference(&firestore, &participant);
+  let mut responses = vec![card_response(card, &board, &participant_id)];
+  responses.extend(
+    db::list_children(&firestore, &board_id, &card_id)
+      .await?
+      .into_iter()
+      .map(|child| card_response(child, &board, &participant_id)),
+  );
+  Ok(HttpResponse::Ok().json(CardResponse::nest(responses).swap_remove(0)))
 }
 
 #[patch("boards/{board_id}/cards/{card_id}")]
 pub async fn update(
   firestore: web::Data<FirestoreDb>,
+  broker: web::Data<Broker>,
   participant: Participant,
   params: web::Path<(String, String)>,
   card_message: web::Json<CardMessage>,
 ) -> Result<HttpResponse, Error> {
   let (board_id, card_id) = params.into_inner();
-  let card_message = card_message.into_inner();
-
-  let card = db::get(&firestore, &board_id, &card_id).await?;
-  super::assert_card_owner(&firestore, &participant, &card, &board_id).await?;
-  let card = db::update(&firestore, &board_id, &card_id, card_message).await?;
-  Ok(
-    HttpResponse::Ok().json(CardResponse::from_card(
-      card,
-      &FirestoreReference(
-        firestore
-          .parent_path("participants", &participant.id)
-          .unwrap()
-          .into(),
-      ),
-    )),
+  let board = assert_board_access(&firestore, &participant, &board_id).await?;
+  let card = super::update_card(
+    &firestore,
+    &participant,
+    &board_id,
+    &card_id,
+    card_message.into_inner(),
   )
+  .await?;
+  broker.publish(
+    &board_id,
+    BoardEvent::CardUpdated {
+      card_id,
+      column_id: card.column_id(),
+    },
+  );
+  Ok(HttpResponse::Ok().json(card_response(
+    card,
+    &board,
+    &participant_reference(&firestore, &participant),
+  )))
 }
 
 #[delete("boards/{board_id}/cards/{card_id}")]
 pub async fn delete(
   firestore: web::Data<FirestoreDb>,
+  broker: web::Data<Broker>,
   participant: Participant,
   params: web::Path<(String, String)>,
 ) -> Result<HttpResponse, Error> {
   let (board_id, card_id) = params.into_inner();
+  assert_board_access(&firestore, &participant, &board_id).await?;
   let card = db::get(&firestore, &board_id, &card_id).await?;
   super::assert_card_owner(&firestore, &participant, &card, &board_id).await?;
   db::delete(&firestore, &board_id, &card_id).await?;
+  broker.publish(&board_id, BoardEvent::CardDeleted { card_id });
+  Ok(HttpResponse::Ok().finish())
+}
+
+#[post("boards/{board_id}/cards/{card_id}/group")]
+pub async fn group(
+  firestore: web::Data<FirestoreDb>,
+  broker: web::Data<Broker>,
+  participant: Participant,
+  params: web::Path<(String, String)>,
+  group_message: web::Json<GroupMessage>,
+) -> Result<HttpResponse, Error> {
+  let (board_id, card_id) = params.into_inner();
+  assert_board_access(&firestore, &participant, &board_id).await?;
+  let moved = super::group_cards(
+    &firestore,
+    &participant,
+    &board_id,
+    &card_id,
+    group_message.into_inner().cards,
+  )
+  .await?;
+  for card in moved {
+    broker.publish(
+      &board_id,
+      BoardEvent::CardUpdated {
+        column_id: card.column_id(),
+        card_id: card.id,
+      },
+    );
+  }
+  Ok(HttpResponse::Created().finish())
+}
+
+#[delete("boards/{board_id}/cards/{card_id}/group")]
+pub async fn ungroup(
+  firestore: web::Data<FirestoreDb>,
+  broker: web::Data<Broker>,
+  participant: Participant,
+  params: web::Path<(String, String)>,
+) -> Result<HttpResponse, Error> {
+  let (board_id, card_id) = params.into_inner();
+  assert_board_access(&firestore, &participant, &board_id).await?;
+  let detached = super::ungroup_card(&firestore, &participant, &board_id, &card_id).await?;
+  for card in detached {
+    broker.publish(
+      &board_id,
+      BoardEvent::CardUpdated {
+        column_id: card.column_id(),
+        card_id: card.id,
+      },
+    );
+  }
   Ok(HttpResponse::Ok().finish())
 }
 
 #[put("boards/{board_id}/cards/{card_id}/vote")]
 pub async fn put_vote(
   firestore: web::Data<FirestoreDb>,
+  broker: web::Data<Broker>,
   participant: Participant,
   params: web::Path<(String, String)>,
 ) -> Result<HttpResponse, Error> {
   let (board_id, card_id) = params.into_inner();
+  assert_board_access(&firestore, &participant, &board_id).await?;
   assert_voting_allowed(&firestore, &board_id).await?;
   db::put_vote(&firestore, &participant, &board_id, &card_id).await?;
+  touch_member(&firestore, &board_id, &participant).await?;
+  broker.publish(&board_id, BoardEvent::CardVoted { card_id });
   Ok(HttpResponse::Created().finish())
 }
 
 #[delete("boards/{board_id}/cards/{card_id}/vote")]
 pub async fn delete_vote(
   firestore: web::Data<FirestoreDb>,
+  broker: web::Data<Broker>,
   participant: Participant,
   params: web::Path<(String, String)>,
 ) -> Result<HttpResponse, Error> {
   let (board_id, card_id) = params.into_inner();
+  assert_board_access(&firestore, &participant, &board_id).await?;
   assert_voting_allowed(&firestore, &board_id).await?;
   db::delete_vote(&firestore, &participant, &board_id, &card_id).await?;
+  touch_member(&firestore, &board_id, &participant).await?;
+  broker.publish(&board_id, BoardEvent::CardVoted { card_id });
+  Ok(HttpResponse::Created().finish())
+}
+
+#[post("boards/{board_id}/cards/{card_id}/vote/increment")]
+pub async fn increment_vote(
+  firestore: web::Data<FirestoreDb>,
+  broker: web::Data<Broker>,
+  participant: Participant,
+  params: web::Path<(String, String)>,
+) -> Result<HttpResponse, Error> {
+  let (board_id, card_id) = params.into_inner();
+  assert_board_access(&firestore, &participant, &board_id).await?;
+  assert_multi_vote_allowed(&firestore, &board_id).await?;
+  db::increment_vote(&firestore, &participant, &board_id, &card_id).await?;
+  touch_member(&firestore, &board_id, &participant).await?;
+  broker.publish(&board_id, BoardEvent::CardVoted { card_id });
+  Ok(HttpResponse::Created().finish())
+}
+
+#[post("boards/{board_id}/cards/{card_id}/vote/decrement")]
+pub async fn decrement_vote(
+  firestore: web::Data<FirestoreDb>,
+  broker: web::Data<Broker>,
+  participant: Participant,
+  params: web::Path<(String, String)>,
+) -> Result<HttpResponse, Error> {
+  let (board_id, card_id) = params.into_inner();
+  assert_board_access(&firestore, &participant, &board_id).await?;
+  assert_multi_vote_allowed(&firestore, &board_id).await?;
+  db::decrement_vote(&firestore, &participant, &board_id, &card_id).await?;
+  touch_member(&firestore, &board_id, &participant).await?;
+  broker.publish(&board_id, BoardEvent::CardVoted { card_id });
   Ok(HttpResponse::Created().finish())
 }
 
+#[get("boards/{board_id}/votes")]
+pub async fn votes(
+  firestore: web::Data<FirestoreDb>,
+  participant: Participant,
+  board_id: web::Path<String>,
+) -> Result<HttpResponse, Error> {
+  let board = assert_board_access(&firestore, &participant, &board_id).await?;
+  let cards = db::list(&firestore, &board_id).await?;
+  Ok(HttpResponse::Ok().json(VotesResponse::new(
+    board.max_votes_per_participant,
+    &cards,
+    &participant_reference(&firestore, &participant),
+  )))
+}
+
 #[put("boards/{board_id}/cards/{card_id}/react")]
 pub async fn put_reaction(
   firestore: web::Data<FirestoreDb>,
+  broker: web::Data<Broker>,
   participant: Participant,
   params: web::Path<(String, String)>,
   react_message: web::Json<ReactMessage>,
 ) -> Result<HttpResponse, Error> {
   let (board_id, card_id) = params.into_inner();
+  assert_board_access(&firestore, &participant, &board_id).await?;
   db::put_reaction(
     &firestore,
     &participant,
@@ -180,33 +311,73 @@ pub async fn put_reaction(
     &react_message.emoji,
   )
   .await?;
+  touch_member(&firestore, &board_id, &participant).await?;
+  broker.publish(&board_id, BoardEvent::CardReacted { card_id });
   Ok(HttpResponse::Created().finish())
 }
 
 #[delete("boards/{board_id}/cards/{card_id}/react")]
 pub async fn delete_reaction(
   firestore: web::Data<FirestoreDb>,
+  broker: web::Data<Broker>,
   participant: Participant,
   params: web::Path<(String, String)>,
 ) -> Result<HttpResponse, Error> {
   let (board_id, card_id) = params.into_inner();
+  assert_board_access(&firestore, &participant, &board_id).await?;
   db::delete_reaction(&firestore, &participant, &board_id, &card_id).await?;
+  touch_member(&firestore, &board_id, &participant).await?;
+  broker.publish(&board_id, BoardEvent::CardReacted { card_id });
   Ok(HttpResponse::Created().finish())
 }
 
 #[get("boards/{board_id}/csv")]
 pub async fn csv(
   firestore: web::Data<FirestoreDb>,
-  _participant: Participant,
+  participant: Participant,
   board_id: web::Path<String>,
 ) -> Result<HttpResponse, Error> {
-  let board = get_board(&firestore, &board_id).await?;
+  let board = assert_board_access(&firestore, &participant, &board_id).await?;
   let columns = get_columns(&firestore, &board_id).await?;
   let mut cards = db::list(&firestore, &board_id).await?;
-  cards.sort_by(|a, b| b.column.0.cmp(&a.column.0));
+  let groups = group_names(&cards);
+  let card_texts: HashMap<String, String> = cards
+    .iter()
+    .map(|card| (card.id.clone(), card.text.clone()))
+    .collect();
+  cards.sort_by(|a, b| {
+    b.column
+      .0
+      .cmp(&a.column.0)
+      .then_with(|| groups.get(&a.id).cmp(&groups.get(&b.id)))
+  });
+  let participant_id = participant_reference(&firestore, &participant);
+  let hide_votes = board.hides_votes_from(&participant_id);
   let mut csv_writer = CSVWriter::from_writer(vec![]);
   for card in cards.into_iter() {
-    csv_writer.serialize(CardCSVRow::from_card(card, &columns))?;
+    let hide_author = board.hides_author_from(&card.owner, &participant_id);
+    let group_name = groups.get(&card.id).cloned().unwrap_or_default();
+    let mut row = CardCSVRow::from_card(card, &columns);
+    row.group = group_name;
+    if hide_votes {
+      row.votes = 0;
+    }
+    if hide_author {
+      row.author = "".into();
+    }
+    csv_writer.serialize(row)?;
+  }
+  let mut body = csv_writer.into_inner()?;
+
+  // Action items follow the cards as a second table, after a blank line
+  let action_items = action_items::db::list(&firestore, &board_id).await?;
+  if !action_items.is_empty() {
+    let mut action_item_writer = CSVWriter::from_writer(vec![]);
+    for action_item in action_items.into_iter() {
+      action_item_writer.serialize(ActionItemCSVRow::from_action_item(action_item, &card_texts))?;
+    }
+    body.push(b'\n');
+    body.extend(action_item_writer.into_inner()?);
   }
   Ok(
     HttpResponse::Ok()
@@ -220,46 +391,6 @@ pub async fn csv(
           ))],
         },
       ))
-      .body(String::from_utf8(csv_writer.into_inner()?)?),
+      .body(String::from_utf8(body)?),
   )
 }
-
-#[cfg(test)]
-mod tests {
-  use super::*;
-
-  fn msg(text: Option<&str>) -> CardMessage {
-    CardMessage { author: None, text: text.map(|s| s.to_string()), column: None }
-  }
-
-  #[test]
-  fn non_empty_text_is_valid() {
-    assert!(validate_card_text(&msg(Some("Hello"))).is_ok());
-  }
-
-  #[test]
-  fn empty_text_is_bad_request() {
-    assert!(matches!(validate_card_text(&msg(Some(""))), Err(Error::BadRequest(_))));
-  }
-
-  #[test]
-  fn empty_text_error_message() {
-    match validate_card_text(&msg(Some(""))) {
-      Err(Error::BadRequest(s)) => assert_eq!(s, "Empty cards are not allowed."),
-      other => panic!("expected BadRequest, got {other:?}"),
-    }
-  }
-
-  #[test]
-  fn missing_text_is_bad_request() {
-    assert!(matches!(validate_card_text(&msg(None)), Err(Error::BadRequest(_))));
-  }
-
-  #[test]
-  fn missing_text_error_message() {
-    match validate_card_text(&msg(None)) {
-      Err(Error::BadRequest(s)) => assert_eq!(s, "Card text must be provided."),
-      other => panic!("expected BadRequest, got {other:?}"),
-    }
-  }
-}
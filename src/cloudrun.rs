@@ -1,4 +1,5 @@
 use crate::error::Error;
+use actix_web::HttpRequest;
 use bytes::Bytes;
 
 pub fn get_metadata(entry: &'static str) -> std::result::Result<Bytes, Error> {
@@ -21,3 +22,17 @@ pub fn get_project_id() -> std::result::Result<String, Error> {
   let project_id = String::from_utf8(bytes.to_vec())?;
   Ok(project_id)
 }
+
+// Cloud Run's front end appends the caller's address to X-Forwarded-For, so the last
+// entry is the one a client can't forge. Falls back to the peer address when run
+// without a proxy.
+pub fn client_address(req: &HttpRequest) -> String {
+  req
+    .headers()
+    .get("X-Forwarded-For")
+    .and_then(|header| header.to_str().ok())
+    .and_then(|header| header.rsplit(',').next())
+    .map(|address| address.trim().to_string())
+    .or_else(|| req.peer_addr().map(|address| address.ip().to_string()))
+    .unwrap_or_default()
+}
@@ -0,0 +1,109 @@
+pub mod models;
+pub mod routes;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::events::models::PresentParticipant;
+
+struct Connected {
+  name: String,
+  connections: usize,
+}
+
+// Tracks which participants have a socket open to each board in this process.
+#[derive(Default)]
+pub struct Presence {
+  boards: Mutex<HashMap<String, HashMap<String, Connected>>>,
+}
+
+impl Presence {
+  pub fn join(&self, board_id: &str, participant: PresentParticipant) -> Vec<PresentParticipant> {
+    let mut boards = self.boards.lock().unwrap();
+    let connected = boards.entry(board_id.to_string()).or_default();
+    let entry = connected.entry(participant.id).or_insert(Connected {
+      name: participant.name.clone(),
+      connections: 0,
+    });
+    entry.name = participant.name;
+    entry.connections += 1;
+    snapshot(connected)
+  }
+
+  pub fn leave(&self, board_id: &str, participant_id: &str) -> Vec<PresentParticipant> {
+    let mut boards = self.boards.lock().unwrap();
+    let Some(connected) = boards.get_mut(board_id) else {
+      return vec![];
+    };
+    if let Some(entry) = connected.get_mut(participant_id) {
+      entry.connections -= 1;
+      if entry.connections == 0 {
+        connected.remove(participant_id);
+      }
+    }
+    let participants = snapshot(connected);
+    if connected.is_empty() {
+      boards.remove(board_id);
+    }
+    participants
+  }
+}
+
+fn snapshot(connected: &HashMap<String, Connected>) -> Vec<PresentParticipant> {
+  let mut participants: Vec<PresentParticipant> = connected
+    .iter()
+    .map(|(id, entry)| PresentParticipant {
+      id: id.clone(),
+      name: entry.name.clone(),
+    })
+    .collect();
+  participants.sort_by(|a, b| (&a.name, &a.id).cmp(&(&b.name, &b.id)));
+  participants
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn participant(id: &str, name: &str) -> PresentParticipant {
+    PresentParticipant {
+      id: id.to_string(),
+      name: name.to_string(),
+    }
+  }
+
+  #[test]
+  fn join_lists_everyone_connected_to_the_board() {
+    let presence = Presence::default();
+    presence.join("b1", participant("p1", "Bob"));
+    let participants = presence.join("b1", participant("p2", "Alice"));
+    assert_eq!(
+      participants,
+      vec![participant("p2", "Alice"), participant("p1", "Bob")]
+    );
+  }
+
+  #[test]
+  fn boards_are_tracked_separately() {
+    let presence = Presence::default();
+    presence.join("b1", participant("p1", "Bob"));
+    let participants = presence.join("b2", participant("p2", "Alice"));
+    assert_eq!(participants, vec![participant("p2", "Alice")]);
+  }
+
+  #[test]
+  fn participant_stays_present_until_last_connection_leaves() {
+    let presence = Presence::default();
+    presence.join("b1", participant("p1", "Bob"));
+    presence.join("b1", participant("p1", "Bob"));
+    assert_eq!(presence.leave("b1", "p1"), vec![participant("p1", "Bob")]);
+    assert!(presence.leave("b1", "p1").is_empty());
+    assert!(presence.boards.lock().unwrap().is_empty());
+  }
+
+  #[test]
+  fn leave_unknown_board_is_empty() {
+    let presence = Presence::default();
+    assert!(presence.leave("b1", "p1").is_empty());
+  }
+}
@@ -0,0 +1,77 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct SocketQuery {
+  pub name: Option<String>,
+}
+
+// Messages a client may send over the board socket.
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Command {
+  CreateCard {
+    column_id: String,
+    text: Option<String>,
+    author: Option<String>,
+  },
+  UpdateCard {
+    card_id: String,
+    text: Option<String>,
+    author: Option<String>,
+    column_id: Option<String>,
+  },
+  Vote {
+    card_id: String,
+  },
+  Unvote {
+    card_id: String,
+  },
+  React {
+    card_id: String,
+    emoji: String,
+  },
+  Unreact {
+    card_id: String,
+  },
+  Typing {
+    column_id: String,
+    typing: bool,
+  },
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn create_card_command_parses() {
+    let command: Command =
+      serde_json::from_str(r#"{"type": "create_card", "column_id": "col1", "text": "Hi"}"#).unwrap();
+    assert_eq!(
+      command,
+      Command::CreateCard {
+        column_id: "col1".to_string(),
+        text: Some("Hi".to_string()),
+        author: None,
+      }
+    );
+  }
+
+  #[test]
+  fn typing_command_parses() {
+    let command: Command =
+      serde_json::from_str(r#"{"type": "typing", "column_id": "col1", "typing": true}"#).unwrap();
+    assert_eq!(
+      command,
+      Command::Typing {
+        column_id: "col1".to_string(),
+        typing: true,
+      }
+    );
+  }
+
+  #[test]
+  fn unknown_command_is_rejected() {
+    assert!(serde_json::from_str::<Command>(r#"{"type": "delete_board"}"#).is_err());
+  }
+}
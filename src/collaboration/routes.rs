@@ -0,0 +1,224 @@
+use std::time::Duration;
+
+use actix_web::rt::time::{interval_at, Instant};
+use actix_web::{get, web, HttpRequest, HttpResponse};
+use actix_ws::{Message, MessageStream, Session};
+use firestore::FirestoreDb;
+use futures::{select, FutureExt, StreamExt};
+use serde_json::json;
+use tokio::sync::broadcast::error::RecvError;
+
+use super::models::{Command, SocketQuery};
+use super::Presence;
+use crate::boards;
+use crate::cards;
+use crate::cards::models::CardMessage;
+use crate::error::Error;
+use crate::events::models::{BoardEvent, PresentParticipant};
+use crate::events::Broker;
+use crate::participants::db::touch_member;
+use crate::participants::models::Participant;
+
+// How often an open socket checks that the participant can still open the board.
+const ACCESS_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+const MAX_NAME_CHARS: usize = 64;
+
+struct Connection {
+  firestore: web::Data<FirestoreDb>,
+  broker: web::Data<Broker>,
+  presence: web::Data<Presence>,
+  participant: Participant,
+  board_id: String,
+}
+
+async fn handle_command(connection: &Connection, text: &str) -> Result<(), Error> {
+  let Connection {
+    firestore,
+    broker,
+    participant,
+    board_id,
+    ..
+  } = connection;
+  let command: Command = serde_json::from_str(text)
+    .map_err(|e| Error::BadRequest(format!("Invalid command: {}", e)))?;
+  let typing = matches!(command, Command::Typing { .. });
+  match command {
+    Command::CreateCard {
+      column_id,
+      text,
+      author,
+    } => {
+      let card_message = CardMessage {
+        author,
+        text,
+        column: None,
+      };
+      let card = cards::new_card(firestore, participant, board_id, &column_id, card_message).await?;
+      broker.publish(
+        board_id,
+        BoardEvent::CardCreated {
+          card_id: card.id,
+          column_id,
+        },
+      );
+    }
+    Command::UpdateCard {
+      card_id,
+      text,
+      author,
+      column_id,
+    } => {
+      let card_message = CardMessage {
+        author,
+        text,
+        column: column_id,
+      };
+      let card = cards::update_card(firestore, participant, board_id, &card_id, card_message).await?;
+      broker.publish(
+        board_id,
+        BoardEvent::CardUpdated {
+          card_id,
+          column_id: card.column_id(),
+        },
+      );
+    }
+    Command::Vote { card_id } => {
+      boards::assert_voting_allowed(firestore, board_id).await?;
+      cards::db::put_vote(firestore, participant, board_id, &card_id).await?;
+      broker.publish(board_id, BoardEvent::CardVoted { card_id });
+    }
+    Command::Unvote { card_id } => {
+      boards::assert_voting_allowed(firestore, board_id).await?;
+      cards::db::delete_vote(firestore, participant, board_id, &card_id).await?;
+      broker.publish(board_id, BoardEvent::CardVoted { card_id });
+    }
+    Command::React { card_id, emoji } => {
+      cards::db::put_reaction(firestore, participant, board_id, &card_id, &emoji).await?;
+      broker.publish(board_id, BoardEvent::CardReacted { card_id });
+    }
+    Command::Unreact { card_id } => {
+      cards::db::delete_reaction(firestore, participant, board_id, &card_id).await?;
+      broker.publish(board_id, BoardEvent::CardReacted { card_id });
+    }
+    Command::Typing { column_id, typing } => broker.publish(
+      board_id,
+      BoardEvent::Typing {
+        participant_id: participant.id.clone(),
+        column_id,
+        typing,
+      },
+    ),
+  }
+  // Typing doesn't change the board, so it doesn't count as being seen.
+  if !typing {
+    touch_member(firestore, board_id, participant).await?;
+  }
+  Ok(())
+}
+
+// A board can be deleted, trashed or made private while sockets are open on it, so access
+// is checked again before each command and on a timer. Other errors keep the socket open.
+async fn lost_access(connection: &Connection) -> bool {
+  let Connection {
+    firestore,
+    participant,
+    board_id,
+    ..
+  } = connection;
+  matches!(
+    boards::assert_board_access(firestore, participant, board_id).await,
+    Err(Error::Forbidden | Error::NotFound)
+  )
+}
+
+async fn run(connection: Connection, name: String, mut session: Session, mut messages: MessageStream) {
+  let Connection {
+    firestore,
+    broker,
+    presence,
+    participant,
+    board_id,
+  } = &connection;
+  let mut events = broker.subscribe(board_id);
+  let _ = touch_member(firestore, board_id, participant).await;
+  let participants = presence.join(
+    board_id,
+    PresentParticipant {
+      id: participant.id.clone(),
+      name,
+    },
+  );
+  broker.publish(board_id, BoardEvent::PresenceChanged { participants });
+  let mut access_check =
+    interval_at(Instant::now() + ACCESS_CHECK_INTERVAL, ACCESS_CHECK_INTERVAL);
+
+  loop {
+    let sent = select! {
+      message = messages.next().fuse() => match message {
+        Some(Ok(Message::Text(_))) if lost_access(&connection).await => break,
+        Some(Ok(Message::Text(text))) => match handle_command(&connection, &text).await {
+          Ok(()) => Ok(()),
+          Err(error) => {
+            session
+              .text(json!({ "type": "error", "error": error.public_message() }).to_string())
+              .await
+          }
+        },
+        Some(Ok(Message::Ping(bytes))) => session.pong(&bytes).await,
+        Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+        Some(Ok(_)) => Ok(()),
+      },
+      event = events.recv().fuse() => match event {
+        Ok(event) => session.text(serde_json::to_string(&event).unwrap()).await,
+        Err(RecvError::Lagged(_)) => Ok(()),
+        Err(RecvError::Closed) => break,
+      },
+      _ = access_check.tick().fuse() => match lost_access(&connection).await {
+        true => break,
+        false => Ok(()),
+      },
+    };
+    if sent.is_err() {
+      break;
+    }
+  }
+
+  drop(events);
+  let participants = presence.leave(board_id, &participant.id);
+  broker.publish(board_id, BoardEvent::PresenceChanged { participants });
+  broker.release(board_id);
+  let _ = touch_member(firestore, board_id, participant).await;
+  let _ = session.close(None).await;
+}
+
+#[allow(clippy::too_many_arguments)]
+#[get("boards/{board_id}/ws")]
+pub async fn socket(
+  request: HttpRequest,
+  body: web::Payload,
+  firestore: web::Data<FirestoreDb>,
+  broker: web::Data<Broker>,
+  presence: web::Data<Presence>,
+  participant: Participant,
+  board_id: web::Path<String>,
+  query: web::Query<SocketQuery>,
+) -> Result<HttpResponse, Error> {
+  let name = query.into_inner().name.unwrap_or_default();
+  if name.chars().count() > MAX_NAME_CHARS {
+    return Err(Error::BadRequest(format!(
+      "Names can be at most {MAX_NAME_CHARS} characters."
+    )));
+  }
+  boards::assert_board_access(&firestore, &participant, &board_id).await?;
+  let (response, session, messages) =
+    actix_ws::handle(&request, body).map_err(|e| Error::BadRequest(e.to_string()))?;
+  let connection = Connection {
+    firestore,
+    broker,
+    presence,
+    participant,
+    board_id: board_id.into_inner(),
+  };
+  actix_web::rt::spawn(run(connection, name, session, messages));
+  Ok(response)
+}
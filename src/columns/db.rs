@@ -1,10 +1,16 @@
 use firestore::paths;
 use firestore::FirestoreDb;
+use firestore::FirestoreReference;
+use firestore::FirestoreTransaction;
 use futures::stream::BoxStream;
 use futures::StreamExt;
 
 use super::models::*;
+use crate::boards::db::MAX_BATCH_WRITES;
+use crate::cards;
+use crate::cards::models::{Card, CardChangeSet, CardInFirestore, CardParentChangeSet};
 use crate::error::Error;
+use crate::transactions;
 
 pub async fn new(
   firestore: &FirestoreDb,
@@ -84,18 +90,155 @@ pub async fn update(
     .map_err(|e| e.into())
 }
 
+// Deletes the column and deals with its cards. A column can hold more cards than a
+// single commit allows, so they're handled a transaction at a time, each reading the
+// board's cards and the column to move them to afresh, and the column goes with the
+// last of them: no card is left pointing at a missing column, and a failure part way
+// leaves a column that can be deleted again. Cascading also detaches cards elsewhere
+// that were grouped under a deleted one. Returns the IDs of the cards deleted or moved.
 pub async fn delete(
   firestore: &FirestoreDb,
   board_id: &String,
   column_id: &String,
-) -> Result<(), Error> {
-  firestore
-    .fluent()
-    .delete()
-    .from("columns")
-    .document_id(column_id)
-    .parent(firestore.parent_path("boards", board_id)?)
-    .execute()
-    .await
-    .map_err(|e| e.into())
+  strategy: &ColumnDeleteStrategy,
+) -> Result<Vec<String>, Error> {
+  let mut card_ids = vec![];
+  loop {
+    let (step_card_ids, done) = delete_step(firestore, board_id, column_id, strategy).await?;
+    card_ids.extend(step_card_ids);
+    if done {
+      return Ok(card_ids);
+    }
+  }
+}
+
+// Returns the IDs of the cards handled, and whether the column itself was deleted.
+async fn delete_step(
+  firestore: &FirestoreDb,
+  board_id: &String,
+  column_id: &String,
+  strategy: &ColumnDeleteStrategy,
+) -> Result<(Vec<String>, bool), Error> {
+  for _ in 0..transactions::ATTEMPTS {
+    let mut transaction = firestore.begin_transaction().await?;
+    let reads = transactions::reads(firestore, &transaction);
+    let cards = async {
+      if let ColumnDeleteStrategy::MoveTo(target_id) = strategy {
+        get(&reads, board_id, target_id).await?;
+      }
+      cards::db::list(&reads, board_id).await
+    }
+    .await;
+    let written = match cards {
+      Ok(cards) => {
+        add_delete_writes(firestore, &mut transaction, board_id, column_id, cards, strategy)
+      }
+      Err(e) => Err(e),
+    };
+    let step = match written {
+      Ok(step) => step,
+      Err(e) => return transactions::abandon(transaction, Err(e)).await,
+    };
+    if transactions::commit(transaction).await? {
+      return Ok(step);
+    }
+  }
+  Err(transactions::contended())
+}
+
+// Adds as many of the cards' writes as fit in one commit, leaving room for deleting
+// the column once every card has been dealt with.
+fn add_delete_writes(
+  firestore: &FirestoreDb,
+  transaction: &mut FirestoreTransaction<'_>,
+  board_id: &String,
+  column_id: &String,
+  cards: Vec<Card>,
+  strategy: &ColumnDeleteStrategy,
+) -> Result<(Vec<String>, bool), Error> {
+  let board_path = firestore.parent_path("boards", board_id)?;
+  let (column_cards, other_cards): (Vec<Card>, Vec<Card>) =
+    cards.into_iter().partition(|card| &card.column_id() == column_id);
+  let mut writes = 0;
+  let mut handled: Vec<String> = vec![];
+
+  match strategy {
+    ColumnDeleteStrategy::IfEmpty => {
+      if !column_cards.is_empty() {
+        return Err(Error::BadRequest(
+          "The column still has cards, so a delete strategy must be given.".into(),
+        ));
+      }
+    }
+    ColumnDeleteStrategy::Cascade => {
+      // Cards grouped under a deleted card are detached before it goes, so a large
+      // group can be spread over several commits.
+      let detach = CardParentChangeSet { parent: None };
+      'cards: for card in &column_cards {
+        for child in other_cards
+          .iter()
+          .filter(|other| other.parent_id().as_ref() == Some(&card.id))
+        {
+          if writes == MAX_BATCH_WRITES - 1 {
+            break 'cards;
+          }
+          firestore
+            .fluent()
+            .update()
+            .fields(paths!(CardInFirestore::parent))
+            .in_col("cards")
+            .document_id(&child.id)
+            .parent(&board_path)
+            .object(&detach)
+            .add_to_transaction(transaction)?;
+          writes += 1;
+        }
+        if writes == MAX_BATCH_WRITES - 1 {
+          break;
+        }
+        firestore
+          .fluent()
+          .delete()
+          .from("cards")
+          .document_id(&card.id)
+          .parent(&board_path)
+          .add_to_transaction(transaction)?;
+        writes += 1;
+        handled.push(card.id.clone());
+      }
+    }
+    ColumnDeleteStrategy::MoveTo(target_id) => {
+      let change_set = CardChangeSet {
+        author: None,
+        text: None,
+        column: Some(FirestoreReference(format!(
+          "{}/columns/{}",
+          board_path, target_id
+        ))),
+      };
+      for card in column_cards.iter().take(MAX_BATCH_WRITES - 1) {
+        firestore
+          .fluent()
+          .update()
+          .fields(paths!(CardInFirestore::column))
+          .in_col("cards")
+          .document_id(&card.id)
+          .parent(&board_path)
+          .object(&change_set)
+          .add_to_transaction(transaction)?;
+        handled.push(card.id.clone());
+      }
+    }
+  }
+  let done = handled.len() == column_cards.len();
+  if done {
+    firestore
+      .fluent()
+      .delete()
+      .from("columns")
+      .document_id(column_id)
+      .parent(&board_path)
+      .add_to_transaction(transaction)?;
+  }
+  Ok((handled, done))
 }
@@ -3,6 +3,8 @@ use firestore::FirestoreTimestamp;
 use serde::{Deserialize, Serialize};
 use serde_json::Map;
 
+use crate::error::Error;
+
 #[derive(Deserialize, Serialize)]
 pub struct ColumnMessage {
   #[serde(skip_serializing_if = "Option::is_none")]
@@ -13,6 +15,22 @@ pub struct ColumnMessage {
   pub position: Option<i64>,
 }
 
+// `strategy=cascade` deletes the column's cards along with it, and `move_to`
+// names a column to move them to instead. Without either, only an empty column
+// can be deleted.
+#[derive(Deserialize, Default)]
+pub struct ColumnDeleteQuery {
+  pub strategy: Option<String>,
+  pub move_to: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ColumnDeleteStrategy {
+  Cascade,
+  MoveTo(String),
+  IfEmpty,
+}
+
 #[derive(Deserialize, Serialize)]
 pub struct Column {
   pub id: String,
@@ -53,6 +71,33 @@ impl From<ColumnMessage> for NewColumn {
   }
 }
 
+impl From<Column> for NewColumn {
+  fn from(column: Column) -> Self {
+    NewColumn {
+      name: column.name,
+      created_at: FirestoreTimestamp(Utc::now()),
+      position: Some(column.position),
+      data: column.data,
+    }
+  }
+}
+
+impl TryFrom<ColumnDeleteQuery> for ColumnDeleteStrategy {
+  type Error = Error;
+
+  fn try_from(query: ColumnDeleteQuery) -> Result<Self, Self::Error> {
+    match (query.strategy.as_deref(), query.move_to) {
+      (None, None) => Ok(ColumnDeleteStrategy::IfEmpty),
+      (Some("cascade"), None) => Ok(ColumnDeleteStrategy::Cascade),
+      (None | Some("move"), Some(column_id)) => Ok(ColumnDeleteStrategy::MoveTo(column_id)),
+      (Some("move"), None) => Err(Error::BadRequest(
+        "A column to move the cards to must be given.".into(),
+      )),
+      _ => Err(Error::BadRequest("Unknown delete strategy.".into())),
+    }
+  }
+}
+
 impl From<ColumnInFirestore> for Column {
   fn from(column: ColumnInFirestore) -> Self {
     Column {
@@ -125,4 +170,47 @@ mod tests {
     let c: Column = column_in_firestore("col2", None).into();
     assert_eq!(c.position, 0);
   }
+
+  fn delete_query(strategy: Option<&str>, move_to: Option<&str>) -> ColumnDeleteQuery {
+    ColumnDeleteQuery {
+      strategy: strategy.map(String::from),
+      move_to: move_to.map(String::from),
+    }
+  }
+
+  #[test]
+  fn delete_strategy_defaults_to_empty_columns_only() {
+    let strategy = ColumnDeleteStrategy::try_from(delete_query(None, None)).unwrap();
+    assert_eq!(strategy, ColumnDeleteStrategy::IfEmpty);
+  }
+
+  #[test]
+  fn delete_strategy_cascade_must_be_asked_for() {
+    let strategy = ColumnDeleteStrategy::try_from(delete_query(Some("cascade"), None)).unwrap();
+    assert_eq!(strategy, ColumnDeleteStrategy::Cascade);
+  }
+
+  #[test]
+  fn delete_strategy_move_to_names_target_column() {
+    let strategy = ColumnDeleteStrategy::try_from(delete_query(None, Some("col2"))).unwrap();
+    assert_eq!(strategy, ColumnDeleteStrategy::MoveTo("col2".into()));
+  }
+
+  #[test]
+  fn delete_strategy_cascade_with_move_to_is_bad_request() {
+    let result = ColumnDeleteStrategy::try_from(delete_query(Some("cascade"), Some("col2")));
+    assert!(matches!(result, Err(Error::BadRequest(_))));
+  }
+
+  #[test]
+  fn delete_strategy_move_without_target_is_bad_request() {
+    let result = ColumnDeleteStrategy::try_from(delete_query(Some("move"), None));
+    assert!(matches!(result, Err(Error::BadRequest(_))));
+  }
+
+  #[test]
+  fn delete_strategy_unknown_is_bad_request() {
+    let result = ColumnDeleteStrategy::try_from(delete_query(Some("archive"), None));
+    assert!(matches!(result, Err(Error::BadRequest(_))));
+  }
 }
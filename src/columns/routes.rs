@@ -3,14 +3,16 @@ use firestore::{FirestoreDb, FirestoreReference};
 use actix_web::{delete, get, patch, post, web, HttpResponse};
 
 use super::db;
-use super::models::ColumnMessage;
+use super::models::{ColumnDeleteQuery, ColumnDeleteStrategy, ColumnMessage};
 use crate::boards;
 use crate::boards::models::Board;
 use crate::error::Error;
+use crate::events::models::BoardEvent;
+use crate::events::Broker;
 use crate::participants::models::Participant;
 
 fn check_board_owner_permission(board: &Board, participant: &FirestoreReference) -> Result<(), Error> {
-  if board.owner == *participant || board.open_permission {
+  if board.facilitated_by(participant) || board.open_permission {
     Ok(())
   } else {
     Err(Error::Forbidden)
@@ -20,20 +22,36 @@ fn check_board_owner_permission(board: &Board, participant: &FirestoreReference)
 #[post("boards/{board_id}/columns")]
 pub async fn new(
   firestore: web::Data<FirestoreDb>,
-  _participant: Participant,
+  broker: web::Data<Broker>,
+  participant: Participant,
   board_id: web::Path<String>,
   column_message: web::Json<ColumnMessage>,
 ) -> Result<HttpResponse, Error> {
+  let board = boards::assert_board_access(&firestore, &participant, &board_id).await?;
+  let participant_reference = FirestoreReference(
+    firestore
+      .parent_path("participants", &participant.id)
+      .unwrap()
+      .into(),
+  );
+  check_board_owner_permission(&board, &participant_reference)?;
   let column = db::new(&firestore, &board_id, column_message.into_inner()).await?;
+  broker.publish(
+    &board_id,
+    BoardEvent::ColumnChanged {
+      column_id: column.id.clone(),
+    },
+  );
   Ok(HttpResponse::Ok().json(column))
 }
 
 #[get("boards/{board_id}/columns")]
 pub async fn list(
   firestore: web::Data<FirestoreDb>,
-  _participant: Participant,
+  participant: Participant,
   board_id: web::Path<String>,
 ) -> Result<HttpResponse, Error> {
+  boards::assert_board_access(&firestore, &participant, &board_id).await?;
   let columns = db::list(&firestore, &board_id).await?;
   Ok(HttpResponse::Ok().json(columns))
 }
@@ -41,10 +59,11 @@ pub async fn list(
 #[get("boards/{board_id}/columns/{column_id}")]
 pub async fn get(
   firestore: web::Data<FirestoreDb>,
-  _participant: Participant,
+  participant: Participant,
   params: web::Path<(String, String)>,
 ) -> Result<HttpResponse, Error> {
   let (board_id, column_id) = params.into_inner();
+  boards::assert_board_access(&firestore, &participant, &board_id).await?;
   let column = db::get(&firestore, &board_id, &column_id).await;
   Ok(HttpResponse::Ok().json(column?))
 }
@@ -52,12 +71,13 @@ pub async fn get(
 #[patch("boards/{board_id}/columns/{column_id}")]
 pub async fn update(
   firestore: web::Data<FirestoreDb>,
+  broker: web::Data<Broker>,
   participant: Participant,
   params: web::Path<(String, String)>,
   column_message: web::Json<ColumnMessage>,
 ) -> Result<HttpResponse, Error> {
   let (board_id, column_id) = params.into_inner();
-  let board = boards::db::get(&firestore, &board_id).await?;
+  let board = boards::assert_board_access(&firestore, &participant, &board_id).await?;
   let participant_reference = FirestoreReference(
     firestore
       .parent_path("participants", &participant.id)
@@ -72,14 +92,17 @@ pub async fn update(
     column_message.into_inner(),
   )
   .await?;
+  broker.publish(&board_id, BoardEvent::ColumnChanged { column_id });
   Ok(HttpResponse::Ok().json(column))
 }
 
 #[delete("boards/{board_id}/columns/{column_id}")]
 pub async fn delete(
   firestore: web::Data<FirestoreDb>,
+  broker: web::Data<Broker>,
   participant: Participant,
   params: web::Path<(String, String)>,
+  query: web::Query<ColumnDeleteQuery>,
 ) -> Result<HttpResponse, Error> {
   let (board_id, column_id) = params.into_inner();
   let participant_reference = FirestoreReference(
@@ -88,9 +111,30 @@ pub async fn delete(
       .unwrap()
       .into(),
   );
-  let board = boards::db::get(&firestore, &board_id).await?;
+  let board = boards::assert_board_access(&firestore, &participant, &board_id).await?;
   check_board_owner_permission(&board, &participant_reference)?;
-  db::delete(&firestore, &board_id, &column_id).await?;
+  let strategy = ColumnDeleteStrategy::try_from(query.into_inner())?;
+  if let ColumnDeleteStrategy::MoveTo(target_id) = &strategy {
+    if target_id == &column_id {
+      return Err(Error::BadRequest(
+        "Cards can't be moved to the column being deleted.".into(),
+      ));
+    }
+  }
+  let card_ids = db::delete(&firestore, &board_id, &column_id, &strategy).await?;
+  for card_id in card_ids {
+    broker.publish(
+      &board_id,
+      match &strategy {
+        ColumnDeleteStrategy::MoveTo(target_id) => BoardEvent::CardUpdated {
+          card_id,
+          column_id: target_id.clone(),
+        },
+        _ => BoardEvent::CardDeleted { card_id },
+      },
+    );
+  }
+  broker.publish(&board_id, BoardEvent::ColumnChanged { column_id });
   Ok(HttpResponse::Ok().finish())
 }
 
@@ -115,6 +159,16 @@ mod tests {
       owner: ref_(owner),
       open_permission: false,
       data: serde_json::Value::Object(Map::new()),
+      max_votes_per_participant: None,
+      multi_vote: false,
+      blind_voting: false,
+      anonymous: false,
+      private: false,
+      join_code: None,
+      deleted_at: None,
+      expire_at: None,
+      facilitators: vec![],
+      ownership_transfers: vec![],
     }
   }
 
@@ -1,4 +1,5 @@
 use actix_web::cookie::SameSite;
+use chrono::Duration;
 use serde::{Deserialize, Serialize};
 use std::env;
 use std::fs::File;
@@ -27,6 +28,40 @@ pub struct Config {
   pub firebase_credentials: GoogleAccountKey,
   pub secure_cookie: bool,
   pub same_site: SameSite,
+  // How long a deleted board stays in the trash before it's purged for good.
+  pub board_restore_window: Duration,
+  // How long a board is kept before it expires, counted from creation or extension.
+  pub board_retention: Duration,
+  // Whether this instance purges boards on a timer. Off by default, as every instance
+  // would otherwise run the same purge; `retrograde-admin purge-expired` is the
+  // supported way to purge on a schedule.
+  pub purge_boards: bool,
+}
+
+// The part of the configuration needed to work on the data alone, so tools like
+// `retrograde-admin` can run without the server's secrets and origins.
+pub struct FirestoreConfig {
+  pub firestore_project: String,
+  pub board_restore_window: Duration,
+}
+
+impl FirestoreConfig {
+  pub fn from_env() -> FirestoreConfig {
+    let firestore_project = match env::var("FIRESTORE_PROJECT") {
+      Ok(s) => s,
+      Err(_) => cloudrun::get_project_id().expect("FIRESTORE_PROJECT environment variable"),
+    };
+
+    let board_restore_window = match env::var("BOARD_RESTORE_WINDOW_DAYS") {
+      Ok(days) => Duration::days(days.parse().expect("BOARD_RESTORE_WINDOW_DAYS to be an integer")),
+      _ => Duration::days(30),
+    };
+
+    FirestoreConfig {
+      firestore_project,
+      board_restore_window,
+    }
+  }
 }
 
 impl Config {
@@ -49,16 +84,16 @@ impl Config {
       Ok(s) => s.as_bytes().to_owned(),
     };
 
+    let FirestoreConfig {
+      firestore_project,
+      board_restore_window,
+    } = FirestoreConfig::from_env();
+
     let port = match env::var("PORT") {
       Ok(port) => port.parse().expect("PORT to be an integer"),
       _ => 8000,
     };
 
-    let firestore_project = match env::var("FIRESTORE_PROJECT") {
-      Ok(s) => s,
-      Err(_) => cloudrun::get_project_id().expect("FIRESTORE_PROJECT environment variable"),
-    };
-
     let google_credentials_file_path = match env::var("FIREBASE_SERVICE_ACCOUNT_CREDENTIALS") {
       Ok(s) => s,
       Err(_) => {
@@ -97,6 +132,16 @@ impl Config {
     }
     .expect("invalid value for SAME_SITE.");
 
+    let board_retention = match env::var("BOARD_RETENTION_DAYS") {
+      Ok(days) => Duration::days(days.parse().expect("BOARD_RETENTION_DAYS to be an integer")),
+      _ => Duration::days(180),
+    };
+
+    let purge_boards = match env::var("PURGE_BOARDS") {
+      Ok(s) => s == "true",
+      Err(_) => false,
+    };
+
     Config {
       port,
       secret_key,
@@ -106,6 +151,9 @@ impl Config {
       firebase_credentials,
       secure_cookie,
       same_site,
+      board_restore_window,
+      board_retention,
+      purge_boards,
     }
   }
 }
@@ -121,6 +169,9 @@ impl Clone for Config {
       firebase_credentials: self.firebase_credentials.clone(),
       secure_cookie: self.secure_cookie,
       same_site: self.same_site,
+      board_restore_window: self.board_restore_window,
+      board_retention: self.board_retention,
+      purge_boards: self.purge_boards,
     }
   }
 }
@@ -2,6 +2,7 @@ use actix_identity::error::LoginError;
 use actix_web::http::StatusCode;
 use actix_web::{HttpResponse, ResponseError};
 use firestore::errors::FirestoreError;
+use firestore::FirestoreBatchWriteResponse;
 use serde::Serialize;
 use serde_json::{json, to_string_pretty};
 use std::fmt::{Display, Formatter, Result as FmtResult};
@@ -12,6 +13,7 @@ pub enum Error {
   NotFound,
   Forbidden,
   BadRequest(String),
+  TooManyRequests,
   Other(String),
 }
 
@@ -21,22 +23,44 @@ impl Display for Error {
   }
 }
 
+impl Error {
+  // The message that is safe to show to clients, logging any internal detail instead.
+  pub fn public_message(&self) -> &str {
+    match self {
+      Error::NotFound => "Not Found",
+      Error::Forbidden => "Forbidden",
+      Error::BadRequest(s) => s.as_str(),
+      Error::TooManyRequests => "Too Many Requests",
+      Error::Other(s) => {
+        error!("{}", s);
+        "Something went wrong"
+      }
+    }
+  }
+}
+
+// Batched writes aren't atomic and report the status of each write instead of
+// failing, so the first failed write is surfaced here.
+pub fn check_batch(response: &FirestoreBatchWriteResponse) -> Result<(), Error> {
+  match response.statuses.iter().find(|status| status.code != 0) {
+    Some(status) => Err(Error::Other(format!("Batched write failed: {}", status.message))),
+    None => Ok(()),
+  }
+}
+
 impl ResponseError for Error {
-  fn error_response(&self) -> HttpResponse {
-    let (status, message, log_message) = match self {
-      Error::NotFound => (StatusCode::NOT_FOUND, "Not Found", None),
-      Error::Forbidden => (StatusCode::FORBIDDEN, "Forbidden", None),
-      Error::BadRequest(s) => (StatusCode::BAD_REQUEST, s.as_str(), None),
-      Error::Other(s) => (
-        StatusCode::INTERNAL_SERVER_ERROR,
-        "Something went wrong",
-        Some(s),
-      ),
-    };
-    if let Some(error) = log_message {
-      error!("{}", error);
+  fn status_code(&self) -> StatusCode {
+    match self {
+      Error::NotFound => StatusCode::NOT_FOUND,
+      Error::Forbidden => StatusCode::FORBIDDEN,
+      Error::BadRequest(_) => StatusCode::BAD_REQUEST,
+      Error::TooManyRequests => StatusCode::TOO_MANY_REQUESTS,
+      Error::Other(_) => StatusCode::INTERNAL_SERVER_ERROR,
     }
-    HttpResponse::build(status).json(json!({ "error": message }))
+  }
+
+  fn error_response(&self) -> HttpResponse {
+    HttpResponse::build(self.status_code()).json(json!({ "error": self.public_message() }))
   }
 }
 
@@ -93,6 +117,14 @@ mod tests {
     );
   }
 
+  #[test]
+  fn too_many_requests_status_is_429() {
+    assert_eq!(
+      Error::TooManyRequests.error_response().status(),
+      StatusCode::TOO_MANY_REQUESTS
+    );
+  }
+
   #[test]
   fn other_status_is_500() {
     assert_eq!(
@@ -101,6 +133,22 @@ mod tests {
     );
   }
 
+  #[test]
+  fn public_message_hides_internal_detail() {
+    assert_eq!(
+      Error::Other("secret db password".into()).public_message(),
+      "Something went wrong"
+    );
+  }
+
+  #[test]
+  fn public_message_forwards_bad_request_message() {
+    assert_eq!(
+      Error::BadRequest("column is required".into()).public_message(),
+      "column is required"
+    );
+  }
+
   #[tokio::test]
   async fn not_found_body_has_error_key() {
     let body = to_bytes(Error::NotFound.error_response().into_body()).await.unwrap();
@@ -0,0 +1,106 @@
+pub mod models;
+pub mod routes;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use tokio::sync::broadcast;
+
+use models::BoardEvent;
+
+// Events that a slow subscriber may fall behind by before it starts missing them.
+const CHANNEL_CAPACITY: usize = 64;
+
+// Fans out board events to every subscriber connected to this process.
+#[derive(Default)]
+pub struct Broker {
+  channels: Mutex<HashMap<String, broadcast::Sender<BoardEvent>>>,
+}
+
+impl Broker {
+  pub fn subscribe(&self, board_id: &str) -> broadcast::Receiver<BoardEvent> {
+    let mut channels = self.channels.lock().unwrap();
+    channels
+      .entry(board_id.to_string())
+      .or_insert_with(|| broadcast::channel(CHANNEL_CAPACITY).0)
+      .subscribe()
+  }
+
+  pub fn publish(&self, board_id: &str, event: BoardEvent) {
+    let mut channels = self.channels.lock().unwrap();
+    let Some(sender) = channels.get(board_id) else {
+      return;
+    };
+    // Sending only fails once every subscriber has gone, so drop the channel too.
+    if sender.send(event).is_err() {
+      channels.remove(board_id);
+    }
+  }
+
+  // Called once a subscriber's receiver has been dropped, so boards that go quiet
+  // after their last subscriber leaves don't keep a channel forever.
+  pub fn release(&self, board_id: &str) {
+    let mut channels = self.channels.lock().unwrap();
+    if channels
+      .get(board_id)
+      .is_some_and(|sender| sender.receiver_count() == 0)
+    {
+      channels.remove(board_id);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn card_deleted(card_id: &str) -> BoardEvent {
+    BoardEvent::CardDeleted {
+      card_id: card_id.to_string(),
+    }
+  }
+
+  #[test]
+  fn subscriber_receives_events_for_its_board() {
+    let broker = Broker::default();
+    let mut receiver = broker.subscribe("b1");
+    broker.publish("b1", card_deleted("c1"));
+    assert_eq!(receiver.try_recv().unwrap(), card_deleted("c1"));
+  }
+
+  #[test]
+  fn subscriber_does_not_receive_events_for_other_boards() {
+    let broker = Broker::default();
+    let mut receiver = broker.subscribe("b1");
+    broker.publish("b2", card_deleted("c1"));
+    assert!(receiver.try_recv().is_err());
+  }
+
+  #[test]
+  fn publish_without_subscribers_is_a_no_op() {
+    let broker = Broker::default();
+    broker.publish("b1", card_deleted("c1"));
+    assert!(broker.channels.lock().unwrap().is_empty());
+  }
+
+  #[test]
+  fn channel_is_dropped_once_all_subscribers_leave() {
+    let broker = Broker::default();
+    drop(broker.subscribe("b1"));
+    broker.publish("b1", card_deleted("c1"));
+    assert!(broker.channels.lock().unwrap().is_empty());
+  }
+
+  #[test]
+  fn channel_is_released_once_the_last_subscriber_leaves() {
+    let broker = Broker::default();
+    let first = broker.subscribe("b1");
+    let second = broker.subscribe("b1");
+    drop(first);
+    broker.release("b1");
+    assert!(broker.channels.lock().unwrap().contains_key("b1"));
+    drop(second);
+    broker.release("b1");
+    assert!(broker.channels.lock().unwrap().is_empty());
+  }
+}
@@ -0,0 +1,89 @@
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct PresentParticipant {
+  pub id: String,
+  pub name: String,
+}
+
+// Events carry identifiers only; clients re-fetch through the REST routes so that
+// each participant gets their own view of the changed resource.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BoardEvent {
+  CardCreated { card_id: String, column_id: String },
+  CardUpdated { card_id: String, column_id: String },
+  CardDeleted { card_id: String },
+  CardVoted { card_id: String },
+  CardReacted { card_id: String },
+  ColumnChanged { column_id: String },
+  ActionItemChanged { action_item_id: String },
+  BoardChanged,
+  PresenceChanged {
+    participants: Vec<PresentParticipant>,
+  },
+  Typing {
+    participant_id: String,
+    column_id: String,
+    typing: bool,
+  },
+}
+
+impl BoardEvent {
+  pub fn name(&self) -> &'static str {
+    match self {
+      BoardEvent::CardCreated { .. } => "card_created",
+      BoardEvent::CardUpdated { .. } => "card_updated",
+      BoardEvent::CardDeleted { .. } => "card_deleted",
+      BoardEvent::CardVoted { .. } => "card_voted",
+      BoardEvent::CardReacted { .. } => "card_reacted",
+      BoardEvent::ColumnChanged { .. } => "column_changed",
+      BoardEvent::ActionItemChanged { .. } => "action_item_changed",
+      BoardEvent::BoardChanged => "board_changed",
+      BoardEvent::PresenceChanged { .. } => "presence_changed",
+      BoardEvent::Typing { .. } => "typing",
+    }
+  }
+
+  pub fn to_sse(&self) -> Bytes {
+    Bytes::from(format!(
+      "event: {}\ndata: {}\n\n",
+      self.name(),
+      serde_json::to_string(self).unwrap()
+    ))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn event_serialises_with_type_tag() {
+    let event = BoardEvent::CardVoted {
+      card_id: "c1".to_string(),
+    };
+    assert_eq!(
+      serde_json::to_value(&event).unwrap(),
+      serde_json::json!({"type": "card_voted", "card_id": "c1"})
+    );
+  }
+
+  #[test]
+  fn event_name_matches_type_tag() {
+    let event = BoardEvent::ColumnChanged {
+      column_id: "col1".to_string(),
+    };
+    assert_eq!(serde_json::to_value(&event).unwrap()["type"], event.name());
+  }
+
+  #[test]
+  fn to_sse_frames_event_and_data_lines() {
+    let event = BoardEvent::BoardChanged;
+    assert_eq!(
+      event.to_sse(),
+      Bytes::from("event: board_changed\ndata: {\"type\":\"board_changed\"}\n\n")
+    );
+  }
+}
@@ -0,0 +1,71 @@
+use std::convert::Infallible;
+use std::time::Duration;
+
+use actix_web::rt::time::interval;
+use actix_web::{get, web, HttpResponse};
+use bytes::Bytes;
+use firestore::FirestoreDb;
+use futures::stream::{select, unfold, Stream};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Receiver;
+
+use super::models::BoardEvent;
+use super::Broker;
+use crate::boards;
+use crate::error::Error;
+use crate::participants::models::Participant;
+
+// Keeps idle connections from being closed by proxies between events.
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+// Releases the board's channel when the client disconnects and the stream is dropped.
+struct Subscription {
+  broker: web::Data<Broker>,
+  board_id: String,
+  receiver: Option<Receiver<BoardEvent>>,
+}
+
+impl Drop for Subscription {
+  fn drop(&mut self) {
+    self.receiver.take();
+    self.broker.release(&self.board_id);
+  }
+}
+
+fn event_stream(subscription: Subscription) -> impl Stream<Item = Result<Bytes, Infallible>> {
+  let events = unfold(subscription, |mut subscription| async move {
+    let receiver = subscription.receiver.as_mut()?;
+    loop {
+      match receiver.recv().await {
+        Ok(event) => return Some((Ok(event.to_sse()), subscription)),
+        Err(RecvError::Lagged(_)) => continue,
+        Err(RecvError::Closed) => return None,
+      }
+    }
+  });
+  let keep_alive = unfold(interval(KEEP_ALIVE_INTERVAL), |mut interval| async move {
+    interval.tick().await;
+    Some((Ok(Bytes::from_static(b": keep-alive\n\n")), interval))
+  });
+  select(events, keep_alive)
+}
+
+#[get("boards/{board_id}/events")]
+pub async fn stream(
+  firestore: web::Data<FirestoreDb>,
+  broker: web::Data<Broker>,
+  participant: Participant,
+  board_id: web::Path<String>,
+) -> Result<HttpResponse, Error> {
+  boards::assert_board_access(&firestore, &participant, &board_id).await?;
+  let subscription = Subscription {
+    receiver: Some(broker.subscribe(&board_id)),
+    broker,
+    board_id: board_id.into_inner(),
+  };
+  Ok(
+    HttpResponse::Ok()
+      .content_type("text/event-stream")
+      .streaming(event_stream(subscription)),
+  )
+}
@@ -0,0 +1,223 @@
+use actix_web::http::StatusCode;
+use actix_web::test::TestRequest;
+use serde_json::json;
+
+use crate::boards;
+use crate::integration_tests::{
+  body_json, emulator_db, make_app, session_cookie, setup_board, setup_board_and_column,
+};
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn create_returns_200_with_action_item_fields() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, cookie) = setup_board(&app).await;
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/action_items"))
+      .cookie(cookie)
+      .set_json(json!({"text": "Fix the build", "assignee": "Sam", "due_date": "2026-11-01"}))
+      .to_request(),
+  )
+  .await;
+
+  assert_eq!(resp.status(), StatusCode::OK);
+  let json = body_json(resp).await;
+  assert_eq!(json["text"], "Fix the build");
+  assert_eq!(json["assignee"], "Sam");
+  assert_eq!(json["due_date"], "2026-11-01");
+  assert_eq!(json["status"], "open");
+  assert_eq!(json["owner"], true);
+  assert!(json["card"].is_null());
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn create_with_malformed_due_date_returns_400() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, cookie) = setup_board(&app).await;
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/action_items"))
+      .cookie(cookie)
+      .set_json(json!({"text": "Fix the build", "due_date": "soon"}))
+      .to_request(),
+  )
+  .await;
+
+  assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn create_linked_to_missing_card_returns_404() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, cookie) = setup_board(&app).await;
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/action_items"))
+      .cookie(cookie)
+      .set_json(json!({"text": "Follow up", "card": "no-such-card"}))
+      .to_request(),
+  )
+  .await;
+
+  assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn create_on_nonexistent_board_returns_404() {
+  let app = make_app!(emulator_db().await);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri("/boards/no-such-board/action_items")
+      .set_json(json!({"text": "Orphan"}))
+      .to_request(),
+  )
+  .await;
+
+  assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn update_status_as_owner_returns_200() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, cookie) = setup_board(&app).await;
+
+  let create_resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/action_items"))
+      .cookie(cookie.clone())
+      .set_json(json!({"text": "Fix the build"}))
+      .to_request(),
+  )
+  .await;
+  let action_item_id = body_json(create_resp).await["id"].as_str().unwrap().to_string();
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::patch()
+      .uri(&format!("/boards/{board_id}/action_items/{action_item_id}"))
+      .cookie(cookie.clone())
+      .set_json(json!({"status": "done"}))
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  assert_eq!(body_json(resp).await["status"], "done");
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards/{board_id}/action_items"))
+      .cookie(cookie)
+      .to_request(),
+  )
+  .await;
+  let json = body_json(resp).await;
+  assert_eq!(json.as_array().unwrap().len(), 1);
+  assert_eq!(json[0]["text"], "Fix the build");
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn delete_as_non_owner_returns_403() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, cookie) = setup_board(&app).await;
+
+  let create_resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/action_items"))
+      .cookie(cookie)
+      .set_json(json!({"text": "Mine"}))
+      .to_request(),
+  )
+  .await;
+  let action_item_id = body_json(create_resp).await["id"].as_str().unwrap().to_string();
+
+  let list_resp =
+    actix_web::test::call_service(&app, TestRequest::get().uri("/boards").to_request()).await;
+  let cookie_b = session_cookie(&list_resp);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::delete()
+      .uri(&format!("/boards/{board_id}/action_items/{action_item_id}"))
+      .cookie(cookie_b)
+      .to_request(),
+  )
+  .await;
+
+  assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn csv_includes_action_items_table() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, col_id, cookie) = setup_board_and_column(&app).await;
+
+  let card_resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/columns/{col_id}/cards"))
+      .cookie(cookie.clone())
+      .set_json(json!({"text": "Deploys are scary"}))
+      .to_request(),
+  )
+  .await;
+  let card_id = body_json(card_resp).await["id"].as_str().unwrap().to_string();
+
+  actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/action_items"))
+      .cookie(cookie.clone())
+      .set_json(json!({"text": "Add a rollback button", "assignee": "Sam", "card": card_id}))
+      .to_request(),
+  )
+  .await;
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards/{board_id}/csv"))
+      .cookie(cookie)
+      .to_request(),
+  )
+  .await;
+  let body = actix_web::test::read_body(resp).await;
+  let csv = String::from_utf8(body.to_vec()).unwrap();
+  assert!(csv.contains("\n\naction_item,assignee,due_date,status,card\n"));
+  assert!(csv.contains("Add a rollback button,Sam,,open,Deploys are scary"));
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
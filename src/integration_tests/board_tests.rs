@@ -3,7 +3,9 @@ use actix_web::test::TestRequest;
 use serde_json::json;
 
 use crate::boards;
-use crate::integration_tests::{body_json, emulator_db, make_app, session_cookie};
+use crate::integration_tests::{
+  body_json, emulator_db, make_app, session_cookie, setup_board, setup_board_and_column,
+};
 
 #[tokio::test]
 #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
@@ -59,7 +61,73 @@ async fn list_returns_200_with_array() {
     actix_web::test::call_service(&app, TestRequest::get().uri("/boards").to_request()).await;
 
   assert_eq!(resp.status(), StatusCode::OK);
-  assert!(body_json(resp).await.is_array());
+  let json = body_json(resp).await;
+  assert!(json["boards"].is_array());
+  assert!(json["next_cursor"].is_null());
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn list_paginates_with_limit_and_cursor() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+
+  let first = actix_web::test::call_service(
+    &app,
+    TestRequest::post().uri("/boards").set_json(json!({"name": "Older"})).to_request(),
+  )
+  .await;
+  let cookie = session_cookie(&first);
+  let older_id = body_json(first).await["id"].as_str().unwrap().to_string();
+  let second = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri("/boards")
+      .cookie(cookie.clone())
+      .set_json(json!({"name": "Newer"}))
+      .to_request(),
+  )
+  .await;
+  let newer_id = body_json(second).await["id"].as_str().unwrap().to_string();
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get().uri("/boards?limit=1").cookie(cookie.clone()).to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  let json = body_json(resp).await;
+  assert_eq!(json["boards"][0]["id"], newer_id.as_str());
+  let cursor = json["next_cursor"].as_str().unwrap().to_string();
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards?limit=1&cursor={cursor}"))
+      .cookie(cookie)
+      .to_request(),
+  )
+  .await;
+  let json = body_json(resp).await;
+  assert_eq!(json["boards"][0]["id"], older_id.as_str());
+  assert!(json["next_cursor"].is_null());
+
+  boards::db::delete(&db, &older_id).await.unwrap();
+  boards::db::delete(&db, &newer_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn list_with_malformed_cursor_returns_400() {
+  let app = make_app!(emulator_db().await);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get().uri("/boards?cursor=not-a-cursor").to_request(),
+  )
+  .await;
+
+  assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
 }
 
 #[tokio::test]
@@ -205,7 +273,8 @@ async fn update_as_non_owner_with_open_permission_returns_200() {
 #[tokio::test]
 #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
 async fn delete_as_owner_returns_200() {
-  let app = make_app!(emulator_db().await);
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
 
   let create_resp = actix_web::test::call_service(
     &app,
@@ -225,6 +294,8 @@ async fn delete_as_owner_returns_200() {
   .await;
 
   assert_eq!(resp.status(), StatusCode::OK);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
 }
 
 #[tokio::test]
@@ -271,3 +342,1069 @@ async fn delete_nonexistent_returns_404() {
 
   assert_eq!(resp.status(), StatusCode::NOT_FOUND);
 }
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn create_with_previous_board_carries_over_open_action_items() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+
+  let previous_resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post().uri("/boards").set_json(json!({"name": "Sprint 1"})).to_request(),
+  )
+  .await;
+  let cookie = session_cookie(&previous_resp);
+  let previous_id = body_json(previous_resp).await["id"].as_str().unwrap().to_string();
+
+  for (text, status) in [("Still to do", "open"), ("Already done", "done")] {
+    actix_web::test::call_service(
+      &app,
+      TestRequest::post()
+        .uri(&format!("/boards/{previous_id}/action_items"))
+        .cookie(cookie.clone())
+        .set_json(json!({"text": text, "status": status, "assignee": "Sam"}))
+        .to_request(),
+    )
+    .await;
+  }
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri("/boards")
+      .cookie(cookie.clone())
+      .set_json(json!({"name": "Sprint 2", "previous_board_id": previous_id}))
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  let board_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards/{board_id}/action_items"))
+      .cookie(cookie)
+      .to_request(),
+  )
+  .await;
+  let json = body_json(resp).await;
+  assert_eq!(json.as_array().unwrap().len(), 1);
+  assert_eq!(json[0]["text"], "Still to do");
+  assert_eq!(json[0]["assignee"], "Sam");
+  assert_eq!(json[0]["owner"], true);
+
+  boards::db::delete(&db, &previous_id).await.unwrap();
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn create_with_carry_over_column_turns_cards_into_action_items() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (previous_id, col_id, cookie) = setup_board_and_column(&app).await;
+
+  actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{previous_id}/columns/{col_id}/cards"))
+      .cookie(cookie.clone())
+      .set_json(json!({"text": "Automate releases"}))
+      .to_request(),
+  )
+  .await;
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri("/boards")
+      .cookie(cookie.clone())
+      .set_json(json!({"previous_board_id": previous_id, "carry_over_column": col_id}))
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  let board_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards/{board_id}/action_items"))
+      .cookie(cookie)
+      .to_request(),
+  )
+  .await;
+  let json = body_json(resp).await;
+  assert_eq!(json.as_array().unwrap().len(), 1);
+  assert_eq!(json[0]["text"], "Automate releases");
+  assert_eq!(json[0]["status"], "open");
+
+  boards::db::delete(&db, &previous_id).await.unwrap();
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn create_with_unjoined_previous_board_returns_403() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (previous_id, _cookie) = setup_board(&app).await;
+
+  // A fresh participant who never opened the previous board
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri("/boards")
+      .set_json(json!({"previous_board_id": previous_id}))
+      .to_request(),
+  )
+  .await;
+
+  assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+  boards::db::delete(&db, &previous_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn create_with_nonexistent_previous_board_returns_404() {
+  let app = make_app!(emulator_db().await);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri("/boards")
+      .set_json(json!({"previous_board_id": "no-such-board"}))
+      .to_request(),
+  )
+  .await;
+
+  assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn create_with_template_creates_columns() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri("/boards")
+      .set_json(json!({"name": "Templated", "template": "4ls"}))
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  let cookie = session_cookie(&resp);
+  let json = body_json(resp).await;
+  assert_eq!(json["cards_open"], true);
+  let board_id = json["id"].as_str().unwrap().to_string();
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards/{board_id}/columns"))
+      .cookie(cookie)
+      .to_request(),
+  )
+  .await;
+  let mut columns = body_json(resp).await.as_array().unwrap().clone();
+  columns.sort_by_key(|column| column["position"].as_i64());
+  let names: Vec<&str> = columns.iter().map(|column| column["name"].as_str().unwrap()).collect();
+  assert_eq!(names, vec!["Liked", "Learned", "Lacked", "Longed For"]);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn create_with_unknown_template_returns_400() {
+  let app = make_app!(emulator_db().await);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri("/boards")
+      .set_json(json!({"template": "no-such-template"}))
+      .to_request(),
+  )
+  .await;
+
+  assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn list_builtin_templates_returns_registry() {
+  let app = make_app!(emulator_db().await);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get().uri("/templates/builtin").to_request(),
+  )
+  .await;
+
+  assert_eq!(resp.status(), StatusCode::OK);
+  let json = body_json(resp).await;
+  assert!(json
+    .as_array()
+    .unwrap()
+    .iter()
+    .any(|template| template["id"] == "start-stop-continue"));
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn clone_copies_settings_and_columns_only_by_default() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri("/boards")
+      .set_json(json!({"name": "Team A", "template": "mad-sad-glad", "multi_vote": true}))
+      .to_request(),
+  )
+  .await;
+  let cookie = session_cookie(&resp);
+  let board_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/clone"))
+      .cookie(cookie.clone())
+      .set_json(json!({"name": "Team B"}))
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  let json = body_json(resp).await;
+  assert_eq!(json["name"], "Team B");
+  assert_eq!(json["multi_vote"], true);
+  assert_eq!(json["owner"], true);
+  let clone_id = json["id"].as_str().unwrap().to_string();
+  assert_ne!(clone_id, board_id);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards/{clone_id}/columns"))
+      .cookie(cookie.clone())
+      .to_request(),
+  )
+  .await;
+  let mut columns = body_json(resp).await.as_array().unwrap().clone();
+  columns.sort_by_key(|column| column["position"].as_i64());
+  let names: Vec<&str> = columns.iter().map(|column| column["name"].as_str().unwrap()).collect();
+  assert_eq!(names, vec!["Mad", "Sad", "Glad"]);
+  assert_eq!(columns[0]["data"], json!({"color": "red"}));
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get().uri("/boards").cookie(cookie).to_request(),
+  )
+  .await;
+  let json = body_json(resp).await;
+  assert!(json["boards"]
+    .as_array()
+    .unwrap()
+    .iter()
+    .any(|board| board["id"] == clone_id.as_str()));
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+  boards::db::delete(&db, &clone_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn clone_with_cards_rewrites_columns_and_groups() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, col_id, cookie) = setup_board_and_column(&app).await;
+
+  let mut card_ids = vec![];
+  for text in ["Parent", "Child"] {
+    let resp = actix_web::test::call_service(
+      &app,
+      TestRequest::post()
+        .uri(&format!("/boards/{board_id}/columns/{col_id}/cards"))
+        .cookie(cookie.clone())
+        .set_json(json!({"text": text}))
+        .to_request(),
+    )
+    .await;
+    card_ids.push(body_json(resp).await["id"].as_str().unwrap().to_string());
+  }
+  actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/cards/{}/group", card_ids[0]))
+      .cookie(cookie.clone())
+      .set_json(json!({"cards": [card_ids[1]]}))
+      .to_request(),
+  )
+  .await;
+  actix_web::test::call_service(
+    &app,
+    TestRequest::put()
+      .uri(&format!("/boards/{board_id}/cards/{}/vote", card_ids[0]))
+      .cookie(cookie.clone())
+      .to_request(),
+  )
+  .await;
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/clone"))
+      .cookie(cookie.clone())
+      .set_json(json!({"include_cards": true}))
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  let clone_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards/{clone_id}/columns"))
+      .cookie(cookie.clone())
+      .to_request(),
+  )
+  .await;
+  let new_col_id = body_json(resp).await[0]["id"].as_str().unwrap().to_string();
+  assert_ne!(new_col_id, col_id);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards/{clone_id}/cards"))
+      .cookie(cookie.clone())
+      .to_request(),
+  )
+  .await;
+  let cards = body_json(resp).await;
+  let cards = cards.as_array().unwrap();
+  assert_eq!(cards.len(), 1);
+  assert_eq!(cards[0]["text"], "Parent");
+  assert_eq!(cards[0]["column"], new_col_id.as_str());
+  assert_eq!(cards[0]["votes"], 0);
+  assert_eq!(cards[0]["children"][0]["text"], "Child");
+  assert_eq!(cards[0]["children"][0]["column"], new_col_id.as_str());
+  assert!(!card_ids.contains(&cards[0]["id"].as_str().unwrap().to_string()));
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/clone"))
+      .cookie(cookie)
+      .set_json(json!({"include_cards": true, "include_votes": true}))
+      .to_request(),
+  )
+  .await;
+  let voted_clone_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+  let cards = crate::cards::db::list(&db, &voted_clone_id).await.unwrap();
+  let parent = cards.iter().find(|card| card.text == "Parent").unwrap();
+  assert_eq!(parent.vote_total(), 1);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+  boards::db::delete(&db, &clone_id).await.unwrap();
+  boards::db::delete(&db, &voted_clone_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn clone_by_non_member_returns_403() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, _) = setup_board(&app).await;
+
+  let resp =
+    actix_web::test::call_service(&app, TestRequest::get().uri("/boards").to_request()).await;
+  let other_cookie = session_cookie(&resp);
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/clone"))
+      .cookie(other_cookie)
+      .set_json(json!({}))
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn deleted_board_is_hidden_until_restored() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, cookie) = setup_board(&app).await;
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::delete()
+      .uri(&format!("/boards/{board_id}"))
+      .cookie(cookie.clone())
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards/{board_id}"))
+      .cookie(cookie.clone())
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get().uri("/boards").cookie(cookie.clone()).to_request(),
+  )
+  .await;
+  assert!(body_json(resp).await["boards"].as_array().unwrap().is_empty());
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/restore"))
+      .cookie(cookie.clone())
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  assert_eq!(body_json(resp).await["id"], board_id.as_str());
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards/{board_id}"))
+      .cookie(cookie)
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn restore_board_that_is_not_deleted_returns_400() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, cookie) = setup_board(&app).await;
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/restore"))
+      .cookie(cookie)
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn restore_as_non_owner_returns_403() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, _) = setup_board(&app).await;
+  boards::db::move_to_trash(&db, &board_id).await.unwrap();
+
+  let resp =
+    actix_web::test::call_service(&app, TestRequest::get().uri("/boards").to_request()).await;
+  let other_cookie = session_cookie(&resp);
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/restore"))
+      .cookie(other_cookie)
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn owner_can_extend_board_expiry() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post().uri("/boards").set_json(json!({})).to_request(),
+  )
+  .await;
+  let cookie = session_cookie(&resp);
+  let json = body_json(resp).await;
+  let board_id = json["id"].as_str().unwrap().to_string();
+  let expire_at = json["expire_at"].as_i64().unwrap();
+  assert!(expire_at > chrono::Utc::now().timestamp());
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/extend"))
+      .cookie(cookie)
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  assert!(body_json(resp).await["expire_at"].as_i64().unwrap() >= expire_at);
+
+  let resp =
+    actix_web::test::call_service(&app, TestRequest::get().uri("/boards").to_request()).await;
+  let other_cookie = session_cookie(&resp);
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/extend"))
+      .cookie(other_cookie)
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+// The participant other than the owner who has joined the board.
+async fn other_member_id(db: &firestore::FirestoreDb, board_id: &String) -> String {
+  let owner = boards::db::get(db, board_id).await.unwrap().owner;
+  let members = db
+    .fluent()
+    .select()
+    .from("members")
+    .parent(db.parent_path("boards", board_id).unwrap())
+    .query()
+    .await
+    .unwrap();
+  members
+    .into_iter()
+    .map(|member| member.name.split('/').next_back().unwrap().to_string())
+    .find(|id| !owner.0.ends_with(&format!("/{id}")))
+    .unwrap()
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn facilitator_runs_the_board_but_cannot_delete_it() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, cookie) = setup_board(&app).await;
+
+  let resp =
+    actix_web::test::call_service(&app, TestRequest::get().uri("/boards").to_request()).await;
+  let other_cookie = session_cookie(&resp);
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards/{board_id}"))
+      .cookie(other_cookie.clone())
+      .to_request(),
+  )
+  .await;
+  assert_eq!(body_json(resp).await["role"], "participant");
+  let other_id = other_member_id(&db, &board_id).await;
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::put()
+      .uri(&format!("/boards/{board_id}/facilitators/{other_id}"))
+      .cookie(other_cookie.clone())
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::put()
+      .uri(&format!("/boards/{board_id}/facilitators/{other_id}"))
+      .cookie(cookie.clone())
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  assert_eq!(body_json(resp).await["facilitators"], json!([other_id]));
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::patch()
+      .uri(&format!("/boards/{board_id}"))
+      .cookie(other_cookie.clone())
+      .set_json(json!({"name": "Facilitated"}))
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  let json = body_json(resp).await;
+  assert_eq!(json["role"], "facilitator");
+  assert_eq!(json["owner"], false);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/columns"))
+      .cookie(other_cookie.clone())
+      .set_json(json!({"name": "Added by facilitator"}))
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::delete()
+      .uri(&format!("/boards/{board_id}"))
+      .cookie(other_cookie.clone())
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::delete()
+      .uri(&format!("/boards/{board_id}/facilitators/{other_id}"))
+      .cookie(cookie)
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  assert_eq!(body_json(resp).await["facilitators"], json!([]));
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::patch()
+      .uri(&format!("/boards/{board_id}"))
+      .cookie(other_cookie)
+      .set_json(json!({"name": "Hijacked"}))
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn add_facilitator_who_has_not_joined_returns_400() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, cookie) = setup_board(&app).await;
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::put()
+      .uri(&format!("/boards/{board_id}/facilitators/not-a-member"))
+      .cookie(cookie.clone())
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::delete()
+      .uri(&format!("/boards/{board_id}/facilitators/not-a-member"))
+      .cookie(cookie)
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn owner_can_transfer_the_board_to_a_member() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, cookie) = setup_board(&app).await;
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/transfer"))
+      .cookie(cookie.clone())
+      .set_json(json!({"participant_id": "not-a-member"}))
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+  let resp =
+    actix_web::test::call_service(&app, TestRequest::get().uri("/boards").to_request()).await;
+  let other_cookie = session_cookie(&resp);
+  actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards/{board_id}"))
+      .cookie(other_cookie.clone())
+      .to_request(),
+  )
+  .await;
+  let other_id = other_member_id(&db, &board_id).await;
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/transfer"))
+      .cookie(other_cookie.clone())
+      .set_json(json!({"participant_id": other_id}))
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/transfer"))
+      .cookie(cookie.clone())
+      .set_json(json!({"participant_id": other_id}))
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  let json = body_json(resp).await;
+  assert_eq!(json["owner"], false);
+  assert_eq!(json["role"], "participant");
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::delete()
+      .uri(&format!("/boards/{board_id}"))
+      .cookie(cookie)
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::delete()
+      .uri(&format!("/boards/{board_id}"))
+      .cookie(other_cookie)
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn private_board_can_only_be_joined_with_an_invite() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post().uri("/boards").set_json(json!({"private": true})).to_request(),
+  )
+  .await;
+  let cookie = session_cookie(&resp);
+  let json = body_json(resp).await;
+  assert_eq!(json["private"], true);
+  let board_id = json["id"].as_str().unwrap().to_string();
+
+  let resp =
+    actix_web::test::call_service(&app, TestRequest::get().uri("/boards").to_request()).await;
+  let other_cookie = session_cookie(&resp);
+  for uri in [
+    format!("/boards/{board_id}"),
+    format!("/boards/{board_id}/columns"),
+    format!("/boards/{board_id}/cards"),
+  ] {
+    let resp = actix_web::test::call_service(
+      &app,
+      TestRequest::get().uri(&uri).cookie(other_cookie.clone()).to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN, "{uri}");
+  }
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/invites"))
+      .cookie(other_cookie.clone())
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+  for body in [json!({}), json!({"invite": "forged"})] {
+    let resp = actix_web::test::call_service(
+      &app,
+      TestRequest::post()
+        .uri(&format!("/boards/{board_id}/join"))
+        .cookie(other_cookie.clone())
+        .set_json(body)
+        .to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+  }
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/invites"))
+      .cookie(cookie)
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  let token = body_json(resp).await["token"].as_str().unwrap().to_string();
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/join"))
+      .cookie(other_cookie.clone())
+      .set_json(json!({"invite": token}))
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards/{board_id}/cards"))
+      .cookie(other_cookie)
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn join_code_can_be_used_regenerated_and_disabled() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post().uri("/boards").set_json(json!({})).to_request(),
+  )
+  .await;
+  let cookie = session_cookie(&resp);
+  let json = body_json(resp).await;
+  let board_id = json["id"].as_str().unwrap().to_string();
+  let join_code = json["join_code"].as_str().unwrap().to_string();
+
+  let resp =
+    actix_web::test::call_service(&app, TestRequest::get().uri("/boards").to_request()).await;
+  let other_cookie = session_cookie(&resp);
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/join/{}", join_code.to_lowercase()))
+      .cookie(other_cookie.clone())
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  assert_eq!(body_json(resp).await["id"], board_id.as_str());
+  // Panics unless the code registered the second participant on the board.
+  other_member_id(&db, &board_id).await;
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/join_code"))
+      .cookie(other_cookie.clone())
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/join_code"))
+      .cookie(cookie.clone())
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  let new_code = body_json(resp).await["join_code"].as_str().unwrap().to_string();
+  assert_ne!(new_code, join_code);
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/join/{join_code}"))
+      .cookie(other_cookie.clone())
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::delete()
+      .uri(&format!("/boards/{board_id}/join_code"))
+      .cookie(cookie.clone())
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  assert!(body_json(resp).await["join_code"].is_null());
+  for request in [
+    TestRequest::get().uri(&format!("/join/{new_code}")).cookie(other_cookie),
+    TestRequest::delete()
+      .uri(&format!("/boards/{board_id}/join_code"))
+      .cookie(cookie),
+  ] {
+    let resp = actix_web::test::call_service(&app, request.to_request()).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+  }
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn guessing_join_codes_is_rate_limited() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+
+  let resp =
+    actix_web::test::call_service(&app, TestRequest::get().uri("/boards").to_request()).await;
+  let cookie = session_cookie(&resp);
+  let guess = |forwarded_for: &str| {
+    TestRequest::get()
+      .uri("/join/NOT-A-CODE")
+      .insert_header(("X-Forwarded-For", forwarded_for))
+      .cookie(cookie.clone())
+      .to_request()
+  };
+  for _ in 0..10 {
+    let resp = actix_web::test::call_service(&app, guess("203.0.113.9")).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+  }
+  // A forged first entry doesn't get around the limit.
+  let resp = actix_web::test::call_service(&app, guess("198.51.100.1, 203.0.113.9")).await;
+  assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+  // Neither does a new address for the same participant.
+  let resp = actix_web::test::call_service(&app, guess("203.0.113.10")).await;
+  assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+
+  let resp =
+    actix_web::test::call_service(&app, TestRequest::get().uri("/boards").to_request()).await;
+  let other_cookie = session_cookie(&resp);
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri("/join/NOT-A-CODE")
+      .insert_header(("X-Forwarded-For", "203.0.113.10"))
+      .cookie(other_cookie)
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn participants_lists_members_with_what_they_contributed() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, col_id, cookie) = setup_board_and_column(&app).await;
+
+  let resp =
+    actix_web::test::call_service(&app, TestRequest::get().uri("/boards").to_request()).await;
+  let other_cookie = session_cookie(&resp);
+  actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards/{board_id}"))
+      .cookie(other_cookie.clone())
+      .to_request(),
+  )
+  .await;
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/columns/{col_id}/cards"))
+      .cookie(other_cookie.clone())
+      .set_json(json!({"text": "Went well", "author": "Sam"}))
+      .to_request(),
+  )
+  .await;
+  let card_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+  actix_web::test::call_service(
+    &app,
+    TestRequest::put()
+      .uri(&format!("/boards/{board_id}/cards/{card_id}/vote"))
+      .cookie(other_cookie.clone())
+      .to_request(),
+  )
+  .await;
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards/{board_id}/participants"))
+      .cookie(other_cookie)
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards/{board_id}/participants"))
+      .cookie(cookie)
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  let json = body_json(resp).await;
+  let members = json.as_array().unwrap();
+  assert_eq!(members.len(), 2);
+  assert_eq!(members[0]["role"], "owner");
+  assert_eq!(members[0]["cards"], 0);
+  assert!(members[0]["name"].is_null());
+  let other = &members[1];
+  assert_eq!(other["id"], other_member_id(&db, &board_id).await.as_str());
+  assert_eq!(other["role"], "participant");
+  assert_eq!(other["name"], "Sam");
+  assert_eq!(other["cards"], 1);
+  assert_eq!(other["votes"], 1);
+  assert_eq!(other["reactions"], 0);
+  assert!(other["joined_at"].as_i64().unwrap() <= other["last_seen"].as_i64().unwrap());
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
@@ -154,6 +154,28 @@ async fn list_returns_200_with_array() {
   boards::db::delete(&db, &board_id).await.unwrap();
 }
 
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn list_for_unknown_board_returns_empty_array() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, _, cookie) = setup_board_and_column(&app).await;
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri("/boards/no-such-board/cards")
+      .cookie(cookie)
+      .to_request(),
+  )
+  .await;
+
+  assert_eq!(resp.status(), StatusCode::OK);
+  assert_eq!(body_json(resp).await, json!([]));
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
 #[tokio::test]
 #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
 async fn get_returns_200() {
@@ -575,6 +597,342 @@ async fn vote_when_voting_closed_returns_403() {
   boards::db::delete(&db, &board_id).await.unwrap();
 }
 
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn vote_beyond_budget_returns_400() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+
+  let board_resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri("/boards")
+      .set_json(json!({"max_votes_per_participant": 1}))
+      .to_request(),
+  )
+  .await;
+  let cookie = session_cookie(&board_resp);
+  let board_json = body_json(board_resp).await;
+  assert_eq!(board_json["max_votes_per_participant"], 1);
+  let board_id = board_json["id"].as_str().unwrap().to_string();
+
+  let col_resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/columns"))
+      .cookie(cookie.clone())
+      .set_json(json!({"name": "Col"}))
+      .to_request(),
+  )
+  .await;
+  let col_id = body_json(col_resp).await["id"].as_str().unwrap().to_string();
+
+  let mut card_ids = vec![];
+  for text in ["First", "Second"] {
+    let card_resp = actix_web::test::call_service(
+      &app,
+      TestRequest::post()
+        .uri(&format!("/boards/{board_id}/columns/{col_id}/cards"))
+        .cookie(cookie.clone())
+        .set_json(json!({"text": text}))
+        .to_request(),
+    )
+    .await;
+    card_ids.push(body_json(card_resp).await["id"].as_str().unwrap().to_string());
+  }
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::put()
+      .uri(&format!("/boards/{board_id}/cards/{}/vote", card_ids[0]))
+      .cookie(cookie.clone())
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::CREATED);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::put()
+      .uri(&format!("/boards/{board_id}/cards/{}/vote", card_ids[1]))
+      .cookie(cookie.clone())
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get().uri(&format!("/boards/{board_id}/votes")).cookie(cookie).to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  let json = body_json(resp).await;
+  assert_eq!(json["max_votes"], 1);
+  assert_eq!(json["votes_cast"], 1);
+  assert_eq!(json["votes_remaining"], 0);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn votes_without_budget_reports_no_limit() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, _col_id, cookie) = setup_board_and_column(&app).await;
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get().uri(&format!("/boards/{board_id}/votes")).cookie(cookie).to_request(),
+  )
+  .await;
+
+  assert_eq!(resp.status(), StatusCode::OK);
+  let json = body_json(resp).await;
+  assert!(json["max_votes"].is_null());
+  assert_eq!(json["votes_cast"], 0);
+  assert!(json["votes_remaining"].is_null());
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn increment_vote_on_multi_vote_board_stacks_votes() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+
+  let board_resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post().uri("/boards").set_json(json!({"multi_vote": true})).to_request(),
+  )
+  .await;
+  let cookie = session_cookie(&board_resp);
+  let board_json = body_json(board_resp).await;
+  assert_eq!(board_json["multi_vote"], true);
+  let board_id = board_json["id"].as_str().unwrap().to_string();
+
+  let col_resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/columns"))
+      .cookie(cookie.clone())
+      .set_json(json!({"name": "Col"}))
+      .to_request(),
+  )
+  .await;
+  let col_id = body_json(col_resp).await["id"].as_str().unwrap().to_string();
+
+  let card_resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/columns/{col_id}/cards"))
+      .cookie(cookie.clone())
+      .set_json(json!({"text": "Stack me"}))
+      .to_request(),
+  )
+  .await;
+  let card_id = body_json(card_resp).await["id"].as_str().unwrap().to_string();
+
+  for _ in 0..2 {
+    let resp = actix_web::test::call_service(
+      &app,
+      TestRequest::post()
+        .uri(&format!("/boards/{board_id}/cards/{card_id}/vote/increment"))
+        .cookie(cookie.clone())
+        .to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+  }
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards/{board_id}/cards/{card_id}"))
+      .cookie(cookie)
+      .to_request(),
+  )
+  .await;
+  let json = body_json(resp).await;
+  assert_eq!(json["votes"], 2);
+  assert_eq!(json["my_votes"], 2);
+  assert_eq!(json["voted"], true);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn increment_vote_without_multi_vote_returns_400() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, col_id, cookie) = setup_board_and_column(&app).await;
+
+  let card_resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/columns/{col_id}/cards"))
+      .cookie(cookie.clone())
+      .set_json(json!({"text": "Single dot"}))
+      .to_request(),
+  )
+  .await;
+  let card_id = body_json(card_resp).await["id"].as_str().unwrap().to_string();
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/cards/{card_id}/vote/increment"))
+      .cookie(cookie)
+      .to_request(),
+  )
+  .await;
+
+  assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn blind_voting_hides_totals_until_voting_closes() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+
+  let board_resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post().uri("/boards").set_json(json!({"blind_voting": true})).to_request(),
+  )
+  .await;
+  let owner_cookie = session_cookie(&board_resp);
+  let board_id = body_json(board_resp).await["id"].as_str().unwrap().to_string();
+
+  let col_resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/columns"))
+      .cookie(owner_cookie.clone())
+      .set_json(json!({"name": "Col"}))
+      .to_request(),
+  )
+  .await;
+  let col_id = body_json(col_resp).await["id"].as_str().unwrap().to_string();
+
+  let card_resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/columns/{col_id}/cards"))
+      .cookie(owner_cookie.clone())
+      .set_json(json!({"text": "Secret ballot"}))
+      .to_request(),
+  )
+  .await;
+  let card_id = body_json(card_resp).await["id"].as_str().unwrap().to_string();
+
+  actix_web::test::call_service(
+    &app,
+    TestRequest::put()
+      .uri(&format!("/boards/{board_id}/cards/{card_id}/vote"))
+      .cookie(owner_cookie.clone())
+      .to_request(),
+  )
+  .await;
+
+  let list_resp =
+    actix_web::test::call_service(&app, TestRequest::get().uri("/boards").to_request()).await;
+  let cookie_b = session_cookie(&list_resp);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards/{board_id}/cards/{card_id}"))
+      .cookie(cookie_b.clone())
+      .to_request(),
+  )
+  .await;
+  let json = body_json(resp).await;
+  assert_eq!(json["votes_hidden"], true);
+  assert_eq!(json["votes"], 0);
+  assert_eq!(json["voted"], false);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards/{board_id}/cards/{card_id}"))
+      .cookie(owner_cookie.clone())
+      .to_request(),
+  )
+  .await;
+  let json = body_json(resp).await;
+  assert_eq!(json["votes_hidden"], false);
+  assert_eq!(json["votes"], 1);
+
+  actix_web::test::call_service(
+    &app,
+    TestRequest::patch()
+      .uri(&format!("/boards/{board_id}"))
+      .cookie(owner_cookie)
+      .set_json(json!({"voting_open": false}))
+      .to_request(),
+  )
+  .await;
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards/{board_id}/cards"))
+      .cookie(cookie_b)
+      .to_request(),
+  )
+  .await;
+  let json = body_json(resp).await;
+  assert_eq!(json[0]["votes_hidden"], false);
+  assert_eq!(json[0]["votes"], 1);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn participant_cannot_turn_off_blind_voting_on_open_board() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+
+  let board_resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri("/boards")
+      .set_json(json!({"blind_voting": true, "open_permission": true}))
+      .to_request(),
+  )
+  .await;
+  let board_id = body_json(board_resp).await["id"].as_str().unwrap().to_string();
+
+  let list_resp =
+    actix_web::test::call_service(&app, TestRequest::get().uri("/boards").to_request()).await;
+  let cookie_b = session_cookie(&list_resp);
+
+  for change in [json!({"blind_voting": false}), json!({"voting_open": false})] {
+    let resp = actix_web::test::call_service(
+      &app,
+      TestRequest::patch()
+        .uri(&format!("/boards/{board_id}"))
+        .cookie(cookie_b.clone())
+        .set_json(change)
+        .to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+  }
+  let board = boards::db::get(&db, &board_id).await.unwrap();
+  assert!(board.blind_voting);
+  assert!(board.voting_open);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
 #[tokio::test]
 #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
 async fn delete_vote_returns_201() {
@@ -724,3 +1082,198 @@ async fn csv_returns_200_with_attachment_header() {
 
   boards::db::delete(&db, &board_id).await.unwrap();
 }
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn anonymous_board_hides_author_from_other_participants() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+
+  let board_resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post().uri("/boards").set_json(json!({"anonymous": true})).to_request(),
+  )
+  .await;
+  let cookie = session_cookie(&board_resp);
+  let board_id = body_json(board_resp).await["id"].as_str().unwrap().to_string();
+
+  let col_resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/columns"))
+      .cookie(cookie.clone())
+      .set_json(json!({"name": "Col"}))
+      .to_request(),
+  )
+  .await;
+  let col_id = body_json(col_resp).await["id"].as_str().unwrap().to_string();
+
+  let card_resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/columns/{col_id}/cards"))
+      .cookie(cookie.clone())
+      .set_json(json!({"text": "Honest feedback", "author": "Alice"}))
+      .to_request(),
+  )
+  .await;
+  let card_json = body_json(card_resp).await;
+  assert_eq!(card_json["author"], "Alice");
+  let card_id = card_json["id"].as_str().unwrap().to_string();
+
+  let list_resp =
+    actix_web::test::call_service(&app, TestRequest::get().uri("/boards").to_request()).await;
+  let cookie_b = session_cookie(&list_resp);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards/{board_id}/cards/{card_id}"))
+      .cookie(cookie_b.clone())
+      .to_request(),
+  )
+  .await;
+  let json = body_json(resp).await;
+  assert_eq!(json["author"], "");
+  assert_eq!(json["text"], "Honest feedback");
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards/{board_id}/csv"))
+      .cookie(cookie_b)
+      .to_request(),
+  )
+  .await;
+  let body = actix_web::test::read_body(resp).await;
+  let csv = String::from_utf8(body.to_vec()).unwrap();
+  assert!(csv.contains("Honest feedback"));
+  assert!(!csv.contains("Alice"));
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn group_nests_cards_and_ungroup_restores_them() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, col_id, cookie) = setup_board_and_column(&app).await;
+
+  let mut card_ids = vec![];
+  for text in ["Slow builds", "CI takes forever"] {
+    let card_resp = actix_web::test::call_service(
+      &app,
+      TestRequest::post()
+        .uri(&format!("/boards/{board_id}/columns/{col_id}/cards"))
+        .cookie(cookie.clone())
+        .set_json(json!({"text": text}))
+        .to_request(),
+    )
+    .await;
+    card_ids.push(body_json(card_resp).await["id"].as_str().unwrap().to_string());
+  }
+  let (parent_id, child_id) = (&card_ids[0], &card_ids[1]);
+
+  actix_web::test::call_service(
+    &app,
+    TestRequest::put()
+      .uri(&format!("/boards/{board_id}/cards/{child_id}/vote"))
+      .cookie(cookie.clone())
+      .to_request(),
+  )
+  .await;
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/cards/{parent_id}/group"))
+      .cookie(cookie.clone())
+      .set_json(json!({"cards": [child_id]}))
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::CREATED);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards/{board_id}/cards"))
+      .cookie(cookie.clone())
+      .to_request(),
+  )
+  .await;
+  let json = body_json(resp).await;
+  assert_eq!(json.as_array().unwrap().len(), 1);
+  assert_eq!(json[0]["id"], parent_id.as_str());
+  assert_eq!(json[0]["votes"], 1);
+  assert_eq!(json[0]["children"][0]["id"], child_id.as_str());
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards/{board_id}/csv"))
+      .cookie(cookie.clone())
+      .to_request(),
+  )
+  .await;
+  let body = actix_web::test::read_body(resp).await;
+  let csv = String::from_utf8(body.to_vec()).unwrap();
+  assert!(csv.starts_with("column,group,"));
+  assert!(csv.contains("Col,Slow builds,,CI takes forever"));
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::delete()
+      .uri(&format!("/boards/{board_id}/cards/{parent_id}/group"))
+      .cookie(cookie.clone())
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards/{board_id}/cards"))
+      .cookie(cookie)
+      .to_request(),
+  )
+  .await;
+  assert_eq!(body_json(resp).await.as_array().unwrap().len(), 2);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn group_under_itself_returns_400() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, col_id, cookie) = setup_board_and_column(&app).await;
+
+  let card_resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/columns/{col_id}/cards"))
+      .cookie(cookie.clone())
+      .set_json(json!({"text": "Lonely"}))
+      .to_request(),
+  )
+  .await;
+  let card_id = body_json(card_resp).await["id"].as_str().unwrap().to_string();
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/cards/{card_id}/group"))
+      .cookie(cookie)
+      .set_json(json!({"cards": [card_id]}))
+      .to_request(),
+  )
+  .await;
+
+  assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
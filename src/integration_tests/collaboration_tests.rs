@@ -0,0 +1,91 @@
+use actix_web::http::StatusCode;
+use actix_web::test::TestRequest;
+
+use crate::boards;
+use crate::integration_tests::{emulator_db, make_app, setup_board};
+
+fn upgrade_request(uri: &str) -> TestRequest {
+  TestRequest::get()
+    .uri(uri)
+    .insert_header(("Upgrade", "websocket"))
+    .insert_header(("Connection", "Upgrade"))
+    .insert_header(("Sec-WebSocket-Version", "13"))
+    .insert_header(("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ=="))
+}
+
+// The socket session is spawned onto the actix runtime, so this needs actix_web::test.
+#[actix_web::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn socket_upgrade_returns_101() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, cookie) = setup_board(&app).await;
+
+  let resp = actix_web::test::call_service(
+    &app,
+    upgrade_request(&format!("/boards/{board_id}/ws?name=Alice"))
+      .cookie(cookie)
+      .to_request(),
+  )
+  .await;
+
+  assert_eq!(resp.status(), StatusCode::SWITCHING_PROTOCOLS);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn socket_without_upgrade_headers_returns_400() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, cookie) = setup_board(&app).await;
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards/{board_id}/ws"))
+      .cookie(cookie)
+      .to_request(),
+  )
+  .await;
+
+  assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn socket_for_nonexistent_board_returns_404() {
+  let app = make_app!(emulator_db().await);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    upgrade_request("/boards/no-such-board/ws").to_request(),
+  )
+  .await;
+
+  assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn socket_with_overlong_name_returns_400() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, cookie) = setup_board(&app).await;
+  let name = "a".repeat(65);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    upgrade_request(&format!("/boards/{board_id}/ws?name={name}"))
+      .cookie(cookie)
+      .to_request(),
+  )
+  .await;
+
+  assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
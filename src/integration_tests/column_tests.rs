@@ -3,7 +3,9 @@ use actix_web::test::TestRequest;
 use serde_json::json;
 
 use crate::boards;
-use crate::integration_tests::{body_json, emulator_db, make_app, session_cookie, setup_board};
+use crate::integration_tests::{
+  body_json, emulator_db, make_app, session_cookie, setup_board, setup_board_and_column,
+};
 
 #[tokio::test]
 #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
@@ -28,6 +30,90 @@ async fn create_returns_200_with_column_fields() {
   boards::db::delete(&db, &board_id).await.unwrap();
 }
 
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn create_as_non_owner_returns_403() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, _) = setup_board(&app).await;
+
+  let list_resp =
+    actix_web::test::call_service(&app, TestRequest::get().uri("/boards").to_request()).await;
+  let cookie_b = session_cookie(&list_resp);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/columns"))
+      .cookie(cookie_b)
+      .set_json(json!({"name": "Intruder"}))
+      .to_request(),
+  )
+  .await;
+
+  assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+  assert!(crate::columns::db::list(&db, &board_id).await.unwrap().is_empty());
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn create_as_non_owner_with_open_permission_returns_200() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+
+  let board_resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri("/boards")
+      .set_json(json!({"open_permission": true}))
+      .to_request(),
+  )
+  .await;
+  let board_id = body_json(board_resp).await["id"].as_str().unwrap().to_string();
+
+  let list_resp =
+    actix_web::test::call_service(&app, TestRequest::get().uri("/boards").to_request()).await;
+  let cookie_b = session_cookie(&list_resp);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/columns"))
+      .cookie(cookie_b)
+      .set_json(json!({"name": "Welcome"}))
+      .to_request(),
+  )
+  .await;
+
+  assert_eq!(resp.status(), StatusCode::OK);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn create_on_missing_board_returns_404() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (_, cookie) = setup_board(&app).await;
+  let board_id = "nonexistent-column-board".to_string();
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/columns"))
+      .cookie(cookie)
+      .set_json(json!({"name": "Orphan"}))
+      .to_request(),
+  )
+  .await;
+
+  assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+  assert!(crate::columns::db::list(&db, &board_id).await.unwrap().is_empty());
+}
+
 #[tokio::test]
 #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
 async fn list_returns_200_with_array() {
@@ -278,3 +364,197 @@ async fn delete_as_non_owner_returns_403() {
 
   boards::db::delete(&db, &board_id).await.unwrap();
 }
+
+async fn add_card(
+  app: &impl actix_web::dev::Service<
+    actix_http::Request,
+    Response = actix_web::dev::ServiceResponse<impl actix_web::body::MessageBody>,
+    Error = actix_web::Error,
+  >,
+  board_id: &str,
+  col_id: &str,
+  cookie: &actix_web::cookie::Cookie<'static>,
+) -> String {
+  let resp = actix_web::test::call_service(
+    app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/columns/{col_id}/cards"))
+      .cookie(cookie.clone())
+      .set_json(json!({"text": "A card"}))
+      .to_request(),
+  )
+  .await;
+  body_json(resp).await["id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn delete_with_cascade_removes_cards() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, col_id, cookie) = setup_board_and_column(&app).await;
+  add_card(&app, &board_id, &col_id, &cookie).await;
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::delete()
+      .uri(&format!("/boards/{board_id}/columns/{col_id}?strategy=cascade"))
+      .cookie(cookie)
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  assert!(crate::cards::db::list(&db, &board_id).await.unwrap().is_empty());
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn delete_without_strategy_keeps_column_with_cards() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, col_id, cookie) = setup_board_and_column(&app).await;
+  add_card(&app, &board_id, &col_id, &cookie).await;
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::delete()
+      .uri(&format!("/boards/{board_id}/columns/{col_id}"))
+      .cookie(cookie)
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+  assert_eq!(crate::cards::db::list(&db, &board_id).await.unwrap().len(), 1);
+  assert!(crate::columns::db::get(&db, &board_id, &col_id).await.is_ok());
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn delete_with_move_to_reassigns_cards() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, col_id, cookie) = setup_board_and_column(&app).await;
+  let card_id = add_card(&app, &board_id, &col_id, &cookie).await;
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/columns"))
+      .cookie(cookie.clone())
+      .set_json(json!({"name": "Target"}))
+      .to_request(),
+  )
+  .await;
+  let target_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::delete()
+      .uri(&format!("/boards/{board_id}/columns/{col_id}?move_to={target_id}"))
+      .cookie(cookie)
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  let card = crate::cards::db::get(&db, &board_id, &card_id).await.unwrap();
+  assert_eq!(card.column_id(), target_id);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn delete_with_invalid_move_target_keeps_column() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, col_id, cookie) = setup_board_and_column(&app).await;
+  add_card(&app, &board_id, &col_id, &cookie).await;
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::delete()
+      .uri(&format!("/boards/{board_id}/columns/{col_id}?move_to={col_id}"))
+      .cookie(cookie.clone())
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::delete()
+      .uri(&format!("/boards/{board_id}/columns/{col_id}?move_to=no-such-column"))
+      .cookie(cookie)
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+  assert_eq!(crate::cards::db::list(&db, &board_id).await.unwrap().len(), 1);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn delete_handles_more_cards_than_a_commit_allows() {
+  use crate::boards::db::MAX_BATCH_WRITES;
+  use crate::cards::models::CardMessage;
+  use crate::participants::models::Participant;
+
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, col_id, cookie) = setup_board_and_column(&app).await;
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri(&format!("/boards/{board_id}/columns"))
+      .cookie(cookie.clone())
+      .set_json(json!({"name": "Target"}))
+      .to_request(),
+  )
+  .await;
+  let target_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+  let participant = Participant { id: "column-delete-test-participant".to_string() };
+  let column = format!("{}/boards/{board_id}/columns/{col_id}", db.get_documents_path());
+  let count = MAX_BATCH_WRITES + 10;
+  futures::future::try_join_all((0..count).map(|i| {
+    crate::cards::db::new(&db, &participant, &board_id, CardMessage {
+      author: None,
+      text: Some(format!("Card {i}")),
+      column: Some(column.clone()),
+    })
+  }))
+  .await
+  .unwrap();
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::delete()
+      .uri(&format!("/boards/{board_id}/columns/{col_id}?move_to={target_id}"))
+      .cookie(cookie.clone())
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  let cards = crate::cards::db::list(&db, &board_id).await.unwrap();
+  assert_eq!(cards.len(), count);
+  assert!(cards.iter().all(|card| card.column_id() == target_id));
+  assert!(crate::columns::db::get(&db, &board_id, &col_id).await.is_err());
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::delete()
+      .uri(&format!("/boards/{board_id}/columns/{target_id}?strategy=cascade"))
+      .cookie(cookie)
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  assert!(crate::cards::db::list(&db, &board_id).await.unwrap().is_empty());
+  assert!(crate::columns::db::get(&db, &board_id, &target_id).await.is_err());
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
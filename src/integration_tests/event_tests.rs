@@ -0,0 +1,42 @@
+use actix_web::http::header::CONTENT_TYPE;
+use actix_web::http::StatusCode;
+use actix_web::test::TestRequest;
+
+use crate::boards;
+use crate::integration_tests::{emulator_db, make_app, setup_board};
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn stream_returns_200_with_event_stream_content_type() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+  let (board_id, cookie) = setup_board(&app).await;
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards/{board_id}/events"))
+      .cookie(cookie)
+      .to_request(),
+  )
+  .await;
+
+  assert_eq!(resp.status(), StatusCode::OK);
+  assert_eq!(resp.headers().get(CONTENT_TYPE).unwrap(), "text/event-stream");
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn stream_for_nonexistent_board_returns_404() {
+  let app = make_app!(emulator_db().await);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get().uri("/boards/no-such-board/events").to_request(),
+  )
+  .await;
+
+  assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
@@ -1,8 +1,12 @@
 // Run with: FIRESTORE_EMULATOR_HOST=localhost:8080 cargo test -- --ignored
 
+mod action_item_tests;
 mod board_tests;
 mod card_tests;
+mod collaboration_tests;
 mod column_tests;
+mod event_tests;
+mod template_tests;
 
 use actix_web::cookie::{Cookie, SameSite};
 use actix_web::test::{self};
@@ -45,6 +49,9 @@ pub(super) fn test_config() -> Config {
     },
     secure_cookie: false,
     same_site: SameSite::Lax,
+    board_restore_window: chrono::Duration::days(30),
+    board_retention: chrono::Duration::days(180),
+    purge_boards: false,
   }
 }
 
@@ -57,6 +64,9 @@ macro_rules! make_app {
       actix_web::App::new()
         .app_data(actix_web::web::Data::new($db))
         .app_data(actix_web::web::Data::new(crate::integration_tests::test_config()))
+        .app_data(actix_web::web::Data::new(crate::events::Broker::default()))
+        .app_data(actix_web::web::Data::new(crate::collaboration::Presence::default()))
+        .app_data(actix_web::web::Data::new(crate::boards::JoinCodeGuard::default()))
         .wrap(actix_identity::IdentityMiddleware::default())
         .wrap(
           actix_session::SessionMiddleware::builder(
@@ -69,9 +79,22 @@ macro_rules! make_app {
         )
         .service(crate::boards::routes::list)
         .service(crate::boards::routes::new)
+        .service(crate::boards::routes::clone)
         .service(crate::boards::routes::update)
         .service(crate::boards::routes::get)
         .service(crate::boards::routes::delete)
+        .service(crate::boards::routes::restore)
+        .service(crate::boards::routes::extend)
+        .service(crate::boards::routes::invite)
+        .service(crate::boards::routes::join)
+        .service(crate::boards::routes::join_by_code)
+        .service(crate::boards::routes::regenerate_join_code)
+        .service(crate::boards::routes::disable_join_code)
+        .service(crate::boards::routes::transfer)
+        .service(crate::boards::routes::facilitators)
+        .service(crate::boards::routes::participants)
+        .service(crate::boards::routes::add_facilitator)
+        .service(crate::boards::routes::remove_facilitator)
         .service(crate::columns::routes::list)
         .service(crate::columns::routes::new)
         .service(crate::columns::routes::update)
@@ -83,10 +106,27 @@ macro_rules! make_app {
         .service(crate::cards::routes::update)
         .service(crate::cards::routes::get)
         .service(crate::cards::routes::delete)
+        .service(crate::cards::routes::group)
+        .service(crate::cards::routes::ungroup)
         .service(crate::cards::routes::put_vote)
         .service(crate::cards::routes::delete_vote)
+        .service(crate::cards::routes::increment_vote)
+        .service(crate::cards::routes::decrement_vote)
+        .service(crate::cards::routes::votes)
         .service(crate::cards::routes::put_reaction)
-        .service(crate::cards::routes::delete_reaction),
+        .service(crate::cards::routes::delete_reaction)
+        .service(crate::action_items::routes::new)
+        .service(crate::action_items::routes::list)
+        .service(crate::action_items::routes::get)
+        .service(crate::action_items::routes::update)
+        .service(crate::action_items::routes::delete)
+        .service(crate::templates::routes::list_builtin)
+        .service(crate::templates::routes::new)
+        .service(crate::templates::routes::list)
+        .service(crate::templates::routes::get)
+        .service(crate::templates::routes::delete)
+        .service(crate::events::routes::stream)
+        .service(crate::collaboration::routes::socket),
     )
     .await
   }};
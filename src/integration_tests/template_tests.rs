@@ -0,0 +1,216 @@
+use actix_web::http::StatusCode;
+use actix_web::test::TestRequest;
+use serde_json::json;
+
+use crate::boards;
+use crate::integration_tests::{body_json, emulator_db, make_app, session_cookie};
+use crate::templates;
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn save_board_as_template_and_list_it() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri("/boards")
+      .set_json(json!({"template": "mad-sad-glad"}))
+      .to_request(),
+  )
+  .await;
+  let cookie = session_cookie(&resp);
+  let board_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri("/templates")
+      .cookie(cookie.clone())
+      .set_json(json!({"name": "Our feelings", "board_id": board_id}))
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  let json = body_json(resp).await;
+  assert_eq!(json["owner"], true);
+  let names: Vec<&str> = json["columns"]
+    .as_array()
+    .unwrap()
+    .iter()
+    .map(|c| c["name"].as_str().unwrap())
+    .collect();
+  assert_eq!(names, vec!["Mad", "Sad", "Glad"]);
+  assert_eq!(json["columns"][0]["data"], json!({"color": "red"}));
+  let template_id = json["id"].as_str().unwrap().to_string();
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri("/templates")
+      .cookie(cookie)
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  let json = body_json(resp).await;
+  let ids: Vec<&str> = json
+    .as_array()
+    .unwrap()
+    .iter()
+    .map(|t| t["id"].as_str().unwrap())
+    .collect();
+  assert_eq!(ids, vec![template_id.as_str()]);
+
+  templates::db::delete(&db, &template_id).await.unwrap();
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn shared_template_creates_board_for_another_participant() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri("/boards")
+      .set_json(json!({"template": "start-stop-continue"}))
+      .to_request(),
+  )
+  .await;
+  let cookie = session_cookie(&resp);
+  let board_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri("/templates")
+      .cookie(cookie)
+      .set_json(json!({"name": "SSC", "board_id": board_id}))
+      .to_request(),
+  )
+  .await;
+  let template_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+
+  // A second participant can view the template by ID, but doesn't own it.
+  let resp =
+    actix_web::test::call_service(&app, TestRequest::get().uri("/boards").to_request()).await;
+  let other_cookie = session_cookie(&resp);
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/templates/{template_id}"))
+      .cookie(other_cookie.clone())
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  assert_eq!(body_json(resp).await["owner"], false);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri("/boards")
+      .cookie(other_cookie.clone())
+      .set_json(json!({"template": template_id}))
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::OK);
+  let new_board_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::get()
+      .uri(&format!("/boards/{new_board_id}/columns"))
+      .cookie(other_cookie.clone())
+      .to_request(),
+  )
+  .await;
+  let mut columns = body_json(resp).await.as_array().unwrap().clone();
+  columns.sort_by_key(|column| column["position"].as_i64());
+  let names: Vec<&str> = columns
+    .iter()
+    .map(|column| column["name"].as_str().unwrap())
+    .collect();
+  assert_eq!(names, vec!["Start", "Stop", "Continue"]);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::delete()
+      .uri(&format!("/templates/{template_id}"))
+      .cookie(other_cookie)
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+  templates::db::delete(&db, &template_id).await.unwrap();
+  boards::db::delete(&db, &board_id).await.unwrap();
+  boards::db::delete(&db, &new_board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn save_template_from_foreign_board_returns_403() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri("/boards")
+      .set_json(json!({}))
+      .to_request(),
+  )
+  .await;
+  let board_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+  let resp =
+    actix_web::test::call_service(&app, TestRequest::get().uri("/boards").to_request()).await;
+  let other_cookie = session_cookie(&resp);
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri("/templates")
+      .cookie(other_cookie)
+      .set_json(json!({"name": "Stolen", "board_id": board_id}))
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+async fn save_template_without_name_returns_400() {
+  let db = emulator_db().await;
+  let app = make_app!(db.clone());
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri("/boards")
+      .set_json(json!({}))
+      .to_request(),
+  )
+  .await;
+  let cookie = session_cookie(&resp);
+  let board_id = body_json(resp).await["id"].as_str().unwrap().to_string();
+
+  let resp = actix_web::test::call_service(
+    &app,
+    TestRequest::post()
+      .uri("/templates")
+      .cookie(cookie)
+      .set_json(json!({"board_id": board_id}))
+      .to_request(),
+  )
+  .await;
+  assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+  boards::db::delete(&db, &board_id).await.unwrap();
+}
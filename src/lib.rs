@@ -0,0 +1,20 @@
+#[macro_use]
+extern crate log;
+
+pub mod action_items;
+pub mod admin;
+pub mod boards;
+pub mod cards;
+mod cloudrun;
+pub mod collaboration;
+pub mod columns;
+pub mod config;
+pub mod error;
+pub mod events;
+pub mod participants;
+pub mod purge;
+pub mod templates;
+pub mod transactions;
+
+#[cfg(test)]
+mod integration_tests;
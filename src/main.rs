@@ -1,17 +1,3 @@
-#[macro_use]
-extern crate log;
-
-mod boards;
-mod cards;
-mod cloudrun;
-mod columns;
-mod config;
-mod error;
-mod participants;
-
-#[cfg(test)]
-mod integration_tests;
-
 use ::firestore::FirestoreDb;
 use actix_cors::Cors;
 use actix_identity::IdentityMiddleware;
@@ -21,6 +7,11 @@ use actix_web::cookie::time::Duration;
 use actix_web::cookie::Key;
 use actix_web::{http, middleware as ActixMiddleware, web::Data, App, HttpServer};
 
+use retrograde::{
+  action_items, boards, cards, collaboration, columns, config, events, participants, purge,
+  templates,
+};
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
   env_logger::init();
@@ -28,6 +19,12 @@ async fn main() -> std::io::Result<()> {
 
   let config = config::Config::from_env();
   let port = config.port;
+  let broker = Data::new(events::Broker::default());
+  let presence = Data::new(collaboration::Presence::default());
+  let join_code_guard = Data::new(boards::JoinCodeGuard::default());
+  if config.purge_boards {
+    purge::spawn(&config);
+  }
 
   HttpServer::new(move || {
     let firestore_project = config.firestore_project.clone();
@@ -45,6 +42,9 @@ async fn main() -> std::io::Result<()> {
     App::new()
       .data_factory(move || FirestoreDb::new(firestore_project.clone()))
       .app_data(Data::new(config.clone()))
+      .app_data(broker.clone())
+      .app_data(presence.clone())
+      .app_data(join_code_guard.clone())
       .wrap(ActixMiddleware::DefaultHeaders::new().add(("Cache-Control", "private")))
       .wrap(cors)
       .wrap(IdentityMiddleware::default())
@@ -64,9 +64,22 @@ async fn main() -> std::io::Result<()> {
       .wrap(ActixMiddleware::Logger::default())
       .service(boards::routes::list)
       .service(boards::routes::new)
+      .service(boards::routes::clone)
       .service(boards::routes::update)
       .service(boards::routes::get)
       .service(boards::routes::delete)
+      .service(boards::routes::restore)
+      .service(boards::routes::extend)
+      .service(boards::routes::invite)
+      .service(boards::routes::join)
+      .service(boards::routes::join_by_code)
+      .service(boards::routes::regenerate_join_code)
+      .service(boards::routes::disable_join_code)
+      .service(boards::routes::transfer)
+      .service(boards::routes::facilitators)
+      .service(boards::routes::participants)
+      .service(boards::routes::add_facilitator)
+      .service(boards::routes::remove_facilitator)
       .service(columns::routes::list)
       .service(columns::routes::new)
       .service(columns::routes::update)
@@ -78,10 +91,27 @@ async fn main() -> std::io::Result<()> {
       .service(cards::routes::update)
       .service(cards::routes::get)
       .service(cards::routes::delete)
+      .service(cards::routes::group)
+      .service(cards::routes::ungroup)
       .service(cards::routes::put_vote)
       .service(cards::routes::delete_vote)
+      .service(cards::routes::increment_vote)
+      .service(cards::routes::decrement_vote)
+      .service(cards::routes::votes)
       .service(cards::routes::put_reaction)
       .service(cards::routes::delete_reaction)
+      .service(action_items::routes::new)
+      .service(action_items::routes::list)
+      .service(action_items::routes::get)
+      .service(action_items::routes::update)
+      .service(action_items::routes::delete)
+      .service(templates::routes::list_builtin)
+      .service(templates::routes::new)
+      .service(templates::routes::list)
+      .service(templates::routes::get)
+      .service(templates::routes::delete)
+      .service(events::routes::stream)
+      .service(collaboration::routes::socket)
       .service(participants::routes::auth)
   })
   .bind(format!("0.0.0.0:{}", port))?
@@ -1,10 +1,23 @@
 use chrono::Utc;
+use firestore::errors::FirestoreError;
 use firestore::path;
+use firestore::paths;
 use firestore::FirestoreDb;
+use firestore::FirestoreQueryCursor;
+use firestore::FirestoreQueryDirection;
 use firestore::FirestoreReference;
+use firestore::FirestoreTimestamp;
+use firestore::FirestoreTransaction;
+use firestore::FirestoreWritePrecondition;
+use futures::stream::BoxStream;
+use futures::StreamExt;
 
 use super::models::*;
-use crate::error::Error;
+use crate::boards::db::MAX_BATCH_WRITES;
+use crate::boards::models::{
+  Board, BoardCreatedAtInFirestore, BoardCursor, BoardInFirestore, BoardRole,
+};
+use crate::error::{check_batch, Error};
 
 pub async fn new(firestore: &FirestoreDb) -> Result<Participant, Error> {
   let new_participant = NewParticipant {
@@ -23,54 +36,412 @@ pub async fn new(firestore: &FirestoreDb) -> Result<Participant, Error> {
     .map_err(|e| e.into())
 }
 
+fn participant_reference(firestore: &FirestoreDb, participant_id: &str) -> FirestoreReference {
+  FirestoreReference(format!(
+    "{}/participants/{}",
+    firestore.get_documents_path(),
+    participant_id
+  ))
+}
+
+// Records the participant as a member of the board, or refreshes when they were last
+// seen and their role if they already are one.
 pub async fn add_participant_board(
   firestore: &FirestoreDb,
   participant: &Participant,
-  board_id: &String,
+  board: &Board,
 ) -> Result<(), Error> {
-  let mut transaction = firestore.begin_transaction().await?;
+  let board_path = firestore.parent_path("boards", &board.id)?;
+  let existing = get_member(firestore, &board.id, &participant.id).await?;
+  let participant_reference = participant_reference(firestore, &participant.id);
+  let (board_created_at, joined_at) = match existing {
+    Some(member) => (member.board_created_at, member.joined_at),
+    None => (
+      board_created_at(firestore, &board.id).await?,
+      FirestoreTimestamp(Utc::now()),
+    ),
+  };
+  let member = NewMember {
+    role: board.role_of(&participant_reference),
+    participant: participant_reference,
+    board_created_at,
+    joined_at,
+    last_seen: FirestoreTimestamp(Utc::now()),
+  };
   firestore
     .fluent()
     .update()
-    .in_col("participants")
+    .in_col("members")
     .document_id(&participant.id)
-    .transforms(|t| {
-      t.fields([t
-        .field(path!(ParticipantInFirestore::boards))
-        .append_missing_elements([FirestoreReference(format!(
-          "{}/boards/{}",
-          firestore.get_documents_path(),
-          board_id
-        ))])])
+    .parent(&board_path)
+    .object(&member)
+    .execute::<MemberInFirestore>()
+    .await?;
+  Ok(())
+}
+
+// `Board` only keeps the creation time to the second, which isn't enough to keep a
+// participant's boards in order.
+async fn board_created_at(
+  firestore: &FirestoreDb,
+  board_id: &String,
+) -> Result<FirestoreTimestamp, Error> {
+  let board: Option<BoardCreatedAtInFirestore> = firestore
+    .fluent()
+    .select()
+    .fields(paths!(BoardInFirestore::{created_at, deleted_at}))
+    .by_id_in("boards")
+    .obj()
+    .one(board_id)
+    .await?;
+  board
+    .map(|board| board.created_at.unwrap_or(board._firestore_created))
+    .ok_or(Error::NotFound)
+}
+
+async fn get_member(
+  firestore: &FirestoreDb,
+  board_id: &String,
+  participant_id: &str,
+) -> Result<Option<MemberInFirestore>, Error> {
+  Ok(
+    firestore
+      .fluent()
+      .select()
+      .by_id_in("members")
+      .parent(firestore.parent_path("boards", board_id)?)
+      .obj()
+      .one(participant_id)
+      .await?,
+  )
+}
+
+// Participants who haven't been through `migrate-members` yet only list their boards
+// in a legacy `boards` array. This gives them a member document, dated from when the
+// board was created, for each board that still exists, and drops the array in the
+// last batch so it's only done once. A failure part way leaves the array in place
+// for the next attempt.
+pub async fn adopt_legacy_boards(
+  firestore: &FirestoreDb,
+  participant: &Participant,
+) -> Result<(), Error> {
+  let legacy: Option<ParticipantInFirestore> = firestore
+    .fluent()
+    .select()
+    .by_id_in("participants")
+    .obj()
+    .one(&participant.id)
+    .await?;
+  let Some(boards) = legacy.and_then(|legacy| legacy.boards) else {
+    return Ok(());
+  };
+  let board_ids = boards.iter().map(|board| board.split('/').next_back().unwrap().to_string());
+  let mut board_stream: BoxStream<(_, Option<BoardInFirestore>)> = firestore
+    .fluent()
+    .select()
+    .by_id_in("boards")
+    .obj()
+    .batch(board_ids)
+    .await?;
+  let participant_reference = participant_reference(firestore, &participant.id);
+  let mut members: Vec<(String, NewMember)> = vec![];
+  while let Some((board_id, board)) = board_stream.next().await {
+    let Some(board) = board else {
+      continue;
+    };
+    if get_member(firestore, &board_id, &participant.id).await?.is_some() {
+      continue;
+    }
+    let created_at = board.created_at.clone().unwrap_or(board._firestore_created.clone());
+    let board: Board = board.into();
+    members.push((
+      board_id,
+      NewMember {
+        role: board.role_of(&participant_reference),
+        participant: participant_reference.clone(),
+        board_created_at: created_at.clone(),
+        joined_at: created_at.clone(),
+        last_seen: created_at,
+      },
+    ));
+  }
+
+  let chunks: Vec<&[(String, NewMember)]> = members.chunks(MAX_BATCH_WRITES - 1).collect();
+  let writer = firestore.create_simple_batch_writer().await?;
+  for i in 0..chunks.len().max(1) {
+    let mut batch = writer.new_batch();
+    for (board_id, member) in chunks.get(i).copied().unwrap_or_default() {
+      firestore
+        .fluent()
+        .update()
+        .in_col("members")
+        .document_id(&participant.id)
+        .parent(firestore.parent_path("boards", board_id)?)
+        .object(member)
+        .add_to_batch(&mut batch)?;
+    }
+    if i + 1 >= chunks.len() {
+      firestore
+        .fluent()
+        .update()
+        .fields(paths!(ParticipantBoards::boards))
+        .in_col("participants")
+        .document_id(&participant.id)
+        .object(&ParticipantBoards { boards: None })
+        .add_to_batch(&mut batch)?;
+    }
+    check_batch(&batch.write().await?)?;
+  }
+  Ok(())
+}
+
+// Marks the participant as seen on the board just now, whenever they do something
+// there. Participants without a member document yet are left for `migrate-members`.
+pub async fn touch_member(
+  firestore: &FirestoreDb,
+  board_id: &String,
+  participant: &Participant,
+) -> Result<(), Error> {
+  let touched = firestore
+    .fluent()
+    .update()
+    .fields(paths!(MemberLastSeen::last_seen))
+    .in_col("members")
+    .precondition(FirestoreWritePrecondition::Exists(true))
+    .document_id(&participant.id)
+    .parent(firestore.parent_path("boards", board_id)?)
+    .object(&MemberLastSeen {
+      last_seen: FirestoreTimestamp(Utc::now()),
     })
-    .only_transform()
-    .add_to_transaction(&mut transaction)?;
-  transaction.commit().await?;
+    .execute::<MemberInFirestore>()
+    .await;
+  match touched {
+    Ok(_) | Err(FirestoreError::DataNotFoundError(_)) => Ok(()),
+    Err(e) => Err(e.into()),
+  }
+}
+
+// Adds a role change to a transaction that's already changing the role on the board.
+// The participant must already have a member document, or the transaction fails.
+pub fn set_member_role(
+  firestore: &FirestoreDb,
+  transaction: &mut FirestoreTransaction<'_>,
+  board_id: &String,
+  participant: &FirestoreReference,
+  role: BoardRole,
+) -> Result<(), Error> {
+  firestore
+    .fluent()
+    .update()
+    .fields(paths!(MemberRole::role))
+    .in_col("members")
+    .precondition(FirestoreWritePrecondition::Exists(true))
+    .document_id(participant.0.split('/').next_back().unwrap())
+    .parent(firestore.parent_path("boards", board_id)?)
+    .object(&MemberRole { role })
+    .add_to_transaction(transaction)?;
   Ok(())
 }
 
+// Members are returned in the order they joined.
+pub async fn list_board_members(
+  firestore: &FirestoreDb,
+  board_id: &String,
+) -> Result<Vec<Member>, Error> {
+  let members: Vec<MemberInFirestore> = firestore
+    .fluent()
+    .select()
+    .from("members")
+    .parent(firestore.parent_path("boards", board_id)?)
+    .order_by([(
+      path!(MemberInFirestore::joined_at),
+      FirestoreQueryDirection::Ascending,
+    )])
+    .obj()
+    .query()
+    .await?;
+  Ok(members.into_iter().map(|member| member.into()).collect())
+}
+
+// Falls back to the legacy `boards` array for participants that haven't been
+// migrated yet.
+pub async fn is_board_member(
+  firestore: &FirestoreDb,
+  participant: &Participant,
+  board_id: &String,
+) -> Result<bool, Error> {
+  Ok(
+    get_member(firestore, board_id, &participant.id).await?.is_some()
+      || legacy_board_ids(firestore, participant).await?.contains(board_id),
+  )
+}
+
+// Member documents are named boards/{board_id}/members/{participant_id}, so the
+// board is found two segments from the end. Boards in the participant's legacy
+// `boards` array are included until they've been migrated.
 pub async fn get_participant_board_ids(
   firestore: &FirestoreDb,
   participant: &Participant,
 ) -> Result<Vec<String>, Error> {
-  let result: Option<ParticipantInFirestore> = firestore
+  let participant_reference = participant_reference(firestore, &participant.id);
+  let members = firestore
+    .fluent()
+    .select()
+    .fields(paths!(MemberInFirestore::participant))
+    .from("members")
+    .all_descendants()
+    .filter(|q| {
+      q.for_all([q
+        .field(path!(MemberInFirestore::participant))
+        .eq(&participant_reference)])
+    })
+    .query()
+    .await?;
+  let mut board_ids: Vec<String> = members
+    .iter()
+    .filter_map(|member| member.name.split('/').rev().nth(2))
+    .map(|board_id| board_id.to_string())
+    .collect();
+  for board_id in legacy_board_ids(firestore, participant).await? {
+    if !board_ids.contains(&board_id) {
+      board_ids.push(board_id);
+    }
+  }
+  Ok(board_ids)
+}
+
+// Pages through the participant's member documents, newest board first, returning
+// where each board sits in the order. Boards created at the same instant are ordered
+// by document name, so the cursor never skips or repeats a board.
+pub async fn list_participant_board_positions(
+  firestore: &FirestoreDb,
+  participant: &Participant,
+  limit: usize,
+  after: Option<&BoardCursor>,
+) -> Result<Vec<BoardCursor>, Error> {
+  let participant_reference = participant_reference(firestore, &participant.id);
+  let query = firestore
+    .fluent()
+    .select()
+    .from("members")
+    .all_descendants()
+    .filter(|q| {
+      q.for_all([q
+        .field(path!(MemberInFirestore::participant))
+        .eq(&participant_reference)])
+    })
+    .order_by([
+      (
+        path!(MemberInFirestore::board_created_at),
+        FirestoreQueryDirection::Descending,
+      ),
+      ("__name__".to_string(), FirestoreQueryDirection::Descending),
+    ])
+    .limit(limit as u32);
+  let query = match after {
+    Some(cursor) => {
+      let member = FirestoreReference(format!(
+        "{}/members/{}",
+        firestore.parent_path("boards", &cursor.id)?,
+        participant.id
+      ));
+      query.start_at(FirestoreQueryCursor::AfterValue(vec![
+        FirestoreTimestamp(cursor.created_at()).into(),
+        member.into(),
+      ]))
+    }
+    None => query,
+  };
+  let members: Vec<MemberPosition> = query.obj().query().await?;
+  Ok(
+    members
+      .into_iter()
+      .filter_map(|member| {
+        let board_id = member._firestore_full_id.split('/').rev().nth(2)?.to_string();
+        Some(BoardCursor::new(member.board_created_at.0, board_id))
+      })
+      .collect(),
+  )
+}
+
+// The boards a participant joined before member documents existed, until the
+// migration moves them across.
+pub async fn legacy_board_ids(
+  firestore: &FirestoreDb,
+  participant: &Participant,
+) -> Result<Vec<String>, Error> {
+  let participant: Option<ParticipantInFirestore> = firestore
     .fluent()
     .select()
     .by_id_in("participants")
     .obj()
     .one(&participant.id)
     .await?;
+  Ok(
+    participant
+      .and_then(|participant| participant.boards)
+      .unwrap_or_default()
+      .iter()
+      .map(|board| board.split('/').next_back().unwrap().to_string())
+      .collect(),
+  )
+}
+
+// Drops a deleted board from every legacy `boards` array that still lists it.
+pub async fn remove_board_from_participants(
+  firestore: &FirestoreDb,
+  board_id: &String,
+) -> Result<(), Error> {
+  let board_reference = FirestoreReference(format!(
+    "{}/boards/{}",
+    firestore.get_documents_path(),
+    board_id
+  ));
+  let participants: Vec<ParticipantInFirestore> = firestore
+    .fluent()
+    .select()
+    .from("participants")
+    .filter(|q| {
+      q.for_all([q
+        .field(path!(ParticipantInFirestore::boards))
+        .array_contains(&board_reference)])
+    })
+    .obj()
+    .query()
+    .await?;
 
-  if let Some(participant) = result {
-    Ok(
-      participant
-        .boards
-        .unwrap_or(vec![])
-        .into_iter()
-        .map(|id| id.split('/').next_back().unwrap().to_string())
-        .collect(),
-    )
-  } else {
-    Ok(vec![])
+  let writer = firestore.create_simple_batch_writer().await?;
+  for chunk in participants.chunks(MAX_BATCH_WRITES) {
+    let mut batch = writer.new_batch();
+    for participant in chunk {
+      firestore
+        .fluent()
+        .update()
+        .in_col("participants")
+        .document_id(&participant._firestore_id)
+        .transforms(|t| {
+          t.fields([t
+            .field(path!(ParticipantInFirestore::boards))
+            .remove_all_from_array([board_reference.clone()])])
+        })
+        .only_transform()
+        .add_to_batch(&mut batch)?;
+    }
+    check_batch(&batch.write().await?)?;
   }
+  Ok(())
+}
+
+// Participants who joined boards before member documents existed and still have a
+// legacy `boards` array.
+pub async fn list_legacy_participants(firestore: &FirestoreDb) -> Result<Vec<Participant>, Error> {
+  let participants: Vec<ParticipantInFirestore> = firestore
+    .fluent()
+    .select()
+    .from("participants")
+    .filter(|q| q.for_all([q.field(path!(ParticipantInFirestore::boards)).is_not_null()]))
+    .obj()
+    .query()
+    .await?;
+  Ok(participants.into_iter().map(|participant| participant.into()).collect())
 }
@@ -1,7 +1,8 @@
+use crate::boards::models::BoardRole;
 use crate::error;
 use actix_web::dev::Payload;
 use actix_web::{FromRequest, HttpRequest};
-use firestore::FirestoreTimestamp;
+use firestore::{FirestoreReference, FirestoreTimestamp};
 use futures::future::Future;
 use serde::{Deserialize, Serialize};
 use std::pin::Pin;
@@ -20,9 +21,65 @@ pub struct NewParticipant {
 pub struct ParticipantInFirestore {
   pub _firestore_id: String,
   pub _firestore_created: FirestoreTimestamp,
+  // Boards joined before membership moved to member documents, kept only until the
+  // migration has moved them across.
   pub boards: Option<Vec<String>>,
 }
 
+// Left empty so that writing it with the boards field mask removes the field.
+#[derive(Deserialize, Serialize)]
+pub struct ParticipantBoards {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub boards: Option<Vec<String>>,
+}
+
+// Membership lives under the board at boards/{board_id}/members/{participant_id},
+// so a participant can join any number of boards without their own document growing.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct NewMember {
+  pub participant: FirestoreReference,
+  // Copied from the board, so a participant's boards can be paged newest first
+  // without reading every board.
+  pub board_created_at: FirestoreTimestamp,
+  pub joined_at: FirestoreTimestamp,
+  pub last_seen: FirestoreTimestamp,
+  pub role: BoardRole,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct MemberInFirestore {
+  pub _firestore_id: String,
+  pub participant: FirestoreReference,
+  pub board_created_at: FirestoreTimestamp,
+  pub joined_at: FirestoreTimestamp,
+  pub last_seen: FirestoreTimestamp,
+  pub role: BoardRole,
+}
+
+// Just enough of a member document to page through a participant's boards.
+#[derive(Deserialize, Debug)]
+pub struct MemberPosition {
+  pub _firestore_full_id: String,
+  pub board_created_at: FirestoreTimestamp,
+}
+
+pub struct Member {
+  pub id: String,
+  pub participant: FirestoreReference,
+  pub joined_at: i64,
+  pub last_seen: i64,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct MemberLastSeen {
+  pub last_seen: FirestoreTimestamp,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct MemberRole {
+  pub role: BoardRole,
+}
+
 impl From<ParticipantInFirestore> for Participant {
   fn from(participant: ParticipantInFirestore) -> Self {
     Participant {
@@ -31,6 +88,17 @@ impl From<ParticipantInFirestore> for Participant {
   }
 }
 
+impl From<MemberInFirestore> for Member {
+  fn from(member: MemberInFirestore) -> Self {
+    Member {
+      id: member._firestore_id,
+      participant: member.participant,
+      joined_at: member.joined_at.0.timestamp(),
+      last_seen: member.last_seen.0.timestamp(),
+    }
+  }
+}
+
 impl FromRequest for Participant {
   type Error = error::Error;
   type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
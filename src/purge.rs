@@ -0,0 +1,38 @@
+use actix_web::rt::time::interval;
+use firestore::FirestoreDb;
+use std::time::Duration;
+
+use crate::boards;
+use crate::config::Config;
+
+// How often boards are checked for being expired, or in the trash past their
+// restore window.
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+pub fn spawn(config: &Config) {
+  let firestore_project = config.firestore_project.clone();
+  let restore_window = config.board_restore_window;
+  actix_web::rt::spawn(async move {
+    let firestore = match FirestoreDb::new(firestore_project).await {
+      Ok(firestore) => firestore,
+      Err(e) => {
+        error!("Unable to start purging boards: {}", e);
+        return;
+      }
+    };
+    let mut interval = interval(PURGE_INTERVAL);
+    loop {
+      interval.tick().await;
+      match boards::db::purge_deleted(&firestore, restore_window).await {
+        Ok(0) => {}
+        Ok(count) => info!("Purged {} deleted boards.", count),
+        Err(e) => error!("Failed to purge deleted boards: {}", e),
+      }
+      match boards::db::purge_expired(&firestore).await {
+        Ok(0) => {}
+        Ok(count) => info!("Purged {} expired boards.", count),
+        Err(e) => error!("Failed to purge expired boards: {}", e),
+      }
+    }
+  });
+}
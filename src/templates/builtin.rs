@@ -0,0 +1,98 @@
+use serde::Serialize;
+use serde_json::json;
+
+use crate::columns::models::ColumnMessage;
+
+#[derive(Serialize)]
+pub struct BuiltinColumn {
+  pub name: &'static str,
+  pub color: &'static str,
+}
+
+#[derive(Serialize)]
+pub struct BuiltinTemplate {
+  pub id: &'static str,
+  pub name: &'static str,
+  pub columns: &'static [BuiltinColumn],
+}
+
+pub const TEMPLATES: &[BuiltinTemplate] = &[
+  BuiltinTemplate {
+    id: "start-stop-continue",
+    name: "Start, Stop, Continue",
+    columns: &[
+      BuiltinColumn { name: "Start", color: "green" },
+      BuiltinColumn { name: "Stop", color: "red" },
+      BuiltinColumn { name: "Continue", color: "blue" },
+    ],
+  },
+  BuiltinTemplate {
+    id: "4ls",
+    name: "4Ls",
+    columns: &[
+      BuiltinColumn { name: "Liked", color: "green" },
+      BuiltinColumn { name: "Learned", color: "blue" },
+      BuiltinColumn { name: "Lacked", color: "orange" },
+      BuiltinColumn { name: "Longed For", color: "purple" },
+    ],
+  },
+  BuiltinTemplate {
+    id: "mad-sad-glad",
+    name: "Mad, Sad, Glad",
+    columns: &[
+      BuiltinColumn { name: "Mad", color: "red" },
+      BuiltinColumn { name: "Sad", color: "blue" },
+      BuiltinColumn { name: "Glad", color: "green" },
+    ],
+  },
+];
+
+pub fn find(id: &str) -> Option<&'static BuiltinTemplate> {
+  TEMPLATES.iter().find(|template| template.id == id)
+}
+
+impl BuiltinTemplate {
+  pub fn columns(&self) -> Vec<ColumnMessage> {
+    self
+      .columns
+      .iter()
+      .enumerate()
+      .map(|(position, column)| ColumnMessage {
+        name: Some(column.name.into()),
+        data: Some(json!({ "color": column.color })),
+        position: Some(position as i64),
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::collections::HashSet;
+
+  #[test]
+  fn template_ids_are_unique() {
+    let ids: HashSet<&str> = TEMPLATES.iter().map(|template| template.id).collect();
+    assert_eq!(ids.len(), TEMPLATES.len());
+  }
+
+  #[test]
+  fn find_returns_known_template() {
+    assert_eq!(find("mad-sad-glad").unwrap().name, "Mad, Sad, Glad");
+  }
+
+  #[test]
+  fn find_returns_none_for_unknown_template() {
+    assert!(find("no-such-template").is_none());
+  }
+
+  #[test]
+  fn columns_are_positioned_in_order_with_data() {
+    let columns = find("start-stop-continue").unwrap().columns();
+    assert_eq!(columns.len(), 3);
+    assert_eq!(columns[1].name.as_deref(), Some("Stop"));
+    assert_eq!(columns[1].position, Some(1));
+    assert_eq!(columns[1].data, Some(json!({"color": "red"})));
+  }
+}
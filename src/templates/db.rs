@@ -0,0 +1,177 @@
+use firestore::path;
+use firestore::FirestoreDb;
+use firestore::FirestoreReference;
+
+use super::models::*;
+use crate::error::Error;
+use crate::participants::models::Participant;
+
+fn participant_reference(firestore: &FirestoreDb, participant: &Participant) -> FirestoreReference {
+  FirestoreReference(format!(
+    "{}/participants/{}",
+    firestore.get_documents_path(),
+    participant.id
+  ))
+}
+
+pub async fn new(
+  firestore: &FirestoreDb,
+  participant: &Participant,
+  mut template: NewTemplate,
+) -> Result<Template, Error> {
+  template.owner = Some(participant_reference(firestore, participant));
+  firestore
+    .fluent()
+    .insert()
+    .into("templates")
+    .generate_document_id()
+    .object(&template)
+    .execute::<TemplateInFirestore>()
+    .await
+    .map(|template| template.into())
+    .map_err(|e| e.into())
+}
+
+pub async fn list(
+  firestore: &FirestoreDb,
+  participant: &Participant,
+) -> Result<Vec<Template>, Error> {
+  let owner = participant_reference(firestore, participant);
+  let mut templates: Vec<Template> = firestore
+    .fluent()
+    .select()
+    .from("templates")
+    .filter(|q| q.for_all([q.field(path!(TemplateInFirestore::owner)).eq(&owner)]))
+    .obj::<TemplateInFirestore>()
+    .query()
+    .await?
+    .into_iter()
+    .map(|template| template.into())
+    .collect();
+  // Sorted here rather than in the query to avoid needing a composite index.
+  templates.sort_by_key(|template| template.created_at);
+  Ok(templates)
+}
+
+pub async fn get(firestore: &FirestoreDb, template_id: &String) -> Result<Template, Error> {
+  firestore
+    .fluent()
+    .select()
+    .by_id_in("templates")
+    .obj::<TemplateInFirestore>()
+    .one(template_id)
+    .await?
+    .ok_or(Error::NotFound)
+    .map(|template| template.into())
+}
+
+pub async fn delete(firestore: &FirestoreDb, template_id: &String) -> Result<(), Error> {
+  firestore
+    .fluent()
+    .delete()
+    .from("templates")
+    .document_id(template_id)
+    .execute()
+    .await
+    .map_err(|e| e.into())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::columns::models::Column;
+
+  // Run with: FIRESTORE_EMULATOR_HOST=localhost:8080 cargo test -- --ignored
+  async fn emulator_db() -> FirestoreDb {
+    use chrono::Utc;
+    use firestore::FirestoreDbOptions;
+    use gcloud_sdk::{ExternalJwtFunctionSource, Token, TokenSourceType};
+    // "owner" is the Firebase emulator's magic token that bypasses security rules,
+    // matching the credential used by Firebase Admin SDKs in emulator mode.
+    let token_source = ExternalJwtFunctionSource::new(|| async {
+      Ok(Token::new(
+        "Bearer".to_string(),
+        "owner".into(),
+        Utc::now() + chrono::Duration::hours(1),
+      ))
+    });
+    FirestoreDb::with_options_token_source(
+      FirestoreDbOptions::new("test-project".to_string()),
+      vec![],
+      TokenSourceType::ExternalSource(Box::new(token_source)),
+    )
+    .await
+    .unwrap()
+  }
+
+  fn participant(id: &str) -> Participant {
+    Participant { id: id.to_string() }
+  }
+
+  fn new_template(name: &str) -> NewTemplate {
+    NewTemplate::from_columns(
+      name.into(),
+      vec![Column {
+        id: "c1".into(),
+        name: "Went well".into(),
+        created_at: 0,
+        data: serde_json::json!({ "color": "green" }),
+        position: 0,
+      }],
+    )
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn new_template_can_be_retrieved_by_id() {
+    let db = emulator_db().await;
+    let created = new(
+      &db,
+      &participant("template-test-owner"),
+      new_template("Ours"),
+    )
+    .await
+    .unwrap();
+    let fetched = get(&db, &created.id).await.unwrap();
+    assert_eq!(fetched.name, "Ours");
+    assert_eq!(fetched.columns.len(), 1);
+    assert_eq!(
+      fetched.columns[0].data,
+      serde_json::json!({ "color": "green" })
+    );
+    delete(&db, &created.id).await.unwrap();
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn list_returns_only_the_participants_templates() {
+    let db = emulator_db().await;
+    let mine = new(
+      &db,
+      &participant("template-test-mine"),
+      new_template("Mine"),
+    )
+    .await
+    .unwrap();
+    let theirs = new(
+      &db,
+      &participant("template-test-theirs"),
+      new_template("Theirs"),
+    )
+    .await
+    .unwrap();
+    let listed = list(&db, &participant("template-test-mine")).await.unwrap();
+    assert!(listed.iter().any(|t| t.id == mine.id));
+    assert!(listed.iter().all(|t| t.id != theirs.id));
+    delete(&db, &mine.id).await.unwrap();
+    delete(&db, &theirs.id).await.unwrap();
+  }
+
+  #[tokio::test]
+  #[ignore = "requires Firestore emulator: FIRESTORE_EMULATOR_HOST=localhost:8080"]
+  async fn get_nonexistent_template_returns_not_found() {
+    let db = emulator_db().await;
+    let result = get(&db, &"nonexistent-template-id".to_string()).await;
+    assert!(matches!(result, Err(Error::NotFound)));
+  }
+}
@@ -0,0 +1,43 @@
+pub mod builtin;
+pub mod db;
+pub mod models;
+pub mod routes;
+
+use firestore::{FirestoreDb, FirestoreReference};
+
+use crate::columns::models::ColumnMessage;
+use crate::error::Error;
+use crate::participants::models::Participant;
+
+pub fn assert_template_owner(
+  firestore: &FirestoreDb,
+  participant: &Participant,
+  template: &models::Template,
+) -> Result<(), Error> {
+  let participant_reference = FirestoreReference(format!(
+    "{}/participants/{}",
+    firestore.get_documents_path(),
+    participant.id
+  ));
+  match template.owner == participant_reference {
+    true => Ok(()),
+    false => Err(Error::Forbidden),
+  }
+}
+
+// Resolves a template ID to the columns it creates, checking the built-in
+// templates before saved ones. Saved templates are shared by ID, so any
+// participant may use one.
+pub async fn template_columns(
+  firestore: &FirestoreDb,
+  template_id: &String,
+) -> Result<Vec<ColumnMessage>, Error> {
+  if let Some(template) = builtin::find(template_id) {
+    return Ok(template.columns());
+  }
+  match db::get(firestore, template_id).await {
+    Ok(template) => Ok(template.column_messages()),
+    Err(Error::NotFound) => Err(Error::BadRequest("Unknown template.".into())),
+    Err(e) => Err(e),
+  }
+}
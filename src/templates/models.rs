@@ -0,0 +1,190 @@
+use chrono::Utc;
+use firestore::{FirestoreReference, FirestoreTimestamp};
+use serde::{Deserialize, Serialize};
+
+use crate::columns::models::{Column, ColumnMessage};
+
+#[derive(Deserialize, Serialize, Default)]
+pub struct TemplateMessage {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub name: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub board_id: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct TemplateColumn {
+  pub name: String,
+  pub position: i64,
+  pub data: serde_json::Value,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct Template {
+  pub id: String,
+  pub owner: FirestoreReference,
+  pub name: String,
+  pub columns: Vec<TemplateColumn>,
+  pub created_at: i64,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct TemplateResponse {
+  pub id: String,
+  pub owner: bool,
+  pub name: String,
+  pub columns: Vec<TemplateColumn>,
+  pub created_at: i64,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct NewTemplate {
+  pub created_at: FirestoreTimestamp,
+  pub owner: Option<FirestoreReference>,
+  pub name: String,
+  pub columns: Vec<TemplateColumn>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct TemplateInFirestore {
+  pub _firestore_id: String,
+  pub _firestore_created: FirestoreTimestamp,
+  pub created_at: Option<FirestoreTimestamp>,
+  pub owner: FirestoreReference,
+  pub name: String,
+  pub columns: Vec<TemplateColumn>,
+}
+
+impl From<Column> for TemplateColumn {
+  fn from(column: Column) -> Self {
+    TemplateColumn {
+      name: column.name,
+      position: column.position,
+      data: column.data,
+    }
+  }
+}
+
+impl From<TemplateColumn> for ColumnMessage {
+  fn from(column: TemplateColumn) -> Self {
+    ColumnMessage {
+      name: Some(column.name),
+      data: Some(column.data),
+      position: Some(column.position),
+    }
+  }
+}
+
+impl NewTemplate {
+  // Columns are stored in board order so templates read the same way the board did.
+  pub fn from_columns(name: String, mut columns: Vec<Column>) -> NewTemplate {
+    columns.sort_by_key(|column| (column.position, column.created_at));
+    NewTemplate {
+      created_at: FirestoreTimestamp(Utc::now()),
+      owner: None,
+      name,
+      columns: columns.into_iter().map(|column| column.into()).collect(),
+    }
+  }
+}
+
+impl From<TemplateInFirestore> for Template {
+  fn from(template: TemplateInFirestore) -> Self {
+    Template {
+      id: template._firestore_id,
+      owner: template.owner,
+      name: template.name,
+      columns: template.columns,
+      created_at: template
+        .created_at
+        .unwrap_or(template._firestore_created)
+        .0
+        .timestamp(),
+    }
+  }
+}
+
+impl Template {
+  pub fn column_messages(&self) -> Vec<ColumnMessage> {
+    self
+      .columns
+      .iter()
+      .cloned()
+      .map(|column| column.into())
+      .collect()
+  }
+}
+
+impl TemplateResponse {
+  pub fn from_template(
+    template: Template,
+    participant_id: &FirestoreReference,
+  ) -> TemplateResponse {
+    TemplateResponse {
+      owner: &template.owner == participant_id,
+      id: template.id,
+      name: template.name,
+      columns: template.columns,
+      created_at: template.created_at,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  fn column(name: &str, position: i64, created_at: i64) -> Column {
+    Column {
+      id: name.to_lowercase(),
+      name: name.to_string(),
+      created_at,
+      data: json!({ "color": "red" }),
+      position,
+    }
+  }
+
+  #[test]
+  fn new_template_orders_columns_by_position() {
+    let template = NewTemplate::from_columns(
+      "Ours".into(),
+      vec![
+        column("Later", 1, 1),
+        column("First", 0, 5),
+        column("Second", 1, 0),
+      ],
+    );
+    let names: Vec<&str> = template.columns.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["First", "Second", "Later"]);
+    assert_eq!(template.columns[0].data, json!({ "color": "red" }));
+  }
+
+  #[test]
+  fn template_column_becomes_column_message() {
+    let message: ColumnMessage = TemplateColumn {
+      name: "Glad".into(),
+      position: 2,
+      data: json!({ "color": "green" }),
+    }
+    .into();
+    assert_eq!(message.name.as_deref(), Some("Glad"));
+    assert_eq!(message.position, Some(2));
+    assert_eq!(message.data, Some(json!({ "color": "green" })));
+  }
+
+  #[test]
+  fn response_reports_ownership() {
+    let template = Template {
+      id: "t1".into(),
+      owner: FirestoreReference("participants/user1".into()),
+      name: "Ours".into(),
+      columns: vec![],
+      created_at: 1_000_000,
+    };
+    let resp =
+      TemplateResponse::from_template(template, &FirestoreReference("participants/user2".into()));
+    assert!(!resp.owner);
+    assert_eq!(resp.name, "Ours");
+  }
+}
@@ -0,0 +1,97 @@
+use actix_web::{delete, get, post, web, HttpResponse};
+use firestore::{FirestoreDb, FirestoreReference};
+
+use super::builtin::TEMPLATES;
+use super::db;
+use super::models::*;
+use crate::boards::assert_board_member;
+use crate::columns::get_columns;
+use crate::error::Error;
+use crate::participants::models::Participant;
+
+fn participant_reference(firestore: &FirestoreDb, participant: &Participant) -> FirestoreReference {
+  FirestoreReference(
+    firestore
+      .parent_path("participants", &participant.id)
+      .unwrap()
+      .into(),
+  )
+}
+
+#[get("templates/builtin")]
+pub async fn list_builtin() -> Result<HttpResponse, Error> {
+  Ok(HttpResponse::Ok().json(TEMPLATES))
+}
+
+#[post("templates")]
+pub async fn new(
+  firestore: web::Data<FirestoreDb>,
+  participant: Participant,
+  template_message: web::Json<TemplateMessage>,
+) -> Result<HttpResponse, Error> {
+  let template_message = template_message.into_inner();
+  let name = template_message
+    .name
+    .filter(|name| !name.is_empty())
+    .ok_or(Error::BadRequest("Template name must be provided.".into()))?;
+  let board_id = template_message.board_id.ok_or(Error::BadRequest(
+    "A board to save must be provided.".into(),
+  ))?;
+  assert_board_member(&firestore, &participant, &board_id).await?;
+  let columns = get_columns(&firestore, &board_id)
+    .await?
+    .into_values()
+    .collect();
+  let template = db::new(
+    &firestore,
+    &participant,
+    NewTemplate::from_columns(name, columns),
+  )
+  .await?;
+  Ok(HttpResponse::Ok().json(TemplateResponse::from_template(
+    template,
+    &participant_reference(&firestore, &participant),
+  )))
+}
+
+#[get("templates")]
+pub async fn list(
+  firestore: web::Data<FirestoreDb>,
+  participant: Participant,
+) -> Result<HttpResponse, Error> {
+  let participant_id = participant_reference(&firestore, &participant);
+  let templates = db::list(&firestore, &participant).await?;
+  Ok(
+    HttpResponse::Ok().json(
+      templates
+        .into_iter()
+        .map(|template| TemplateResponse::from_template(template, &participant_id))
+        .collect::<Vec<TemplateResponse>>(),
+    ),
+  )
+}
+
+#[get("templates/{template_id}")]
+pub async fn get(
+  firestore: web::Data<FirestoreDb>,
+  participant: Participant,
+  template_id: web::Path<String>,
+) -> Result<HttpResponse, Error> {
+  let template = db::get(&firestore, &template_id).await?;
+  Ok(HttpResponse::Ok().json(TemplateResponse::from_template(
+    template,
+    &participant_reference(&firestore, &participant),
+  )))
+}
+
+#[delete("templates/{template_id}")]
+pub async fn delete(
+  firestore: web::Data<FirestoreDb>,
+  participant: Participant,
+  template_id: web::Path<String>,
+) -> Result<HttpResponse, Error> {
+  let template = db::get(&firestore, &template_id).await?;
+  super::assert_template_owner(&firestore, &participant, &template)?;
+  db::delete(&firestore, &template_id).await?;
+  Ok(HttpResponse::Ok().finish())
+}
@@ -0,0 +1,39 @@
+use firestore::errors::FirestoreError;
+use firestore::{FirestoreConsistencySelector, FirestoreDb, FirestoreTransaction};
+
+use crate::error::Error;
+
+// Firestore aborts a transaction when another commit changes a document it read, so
+// it's run again this many times before giving up.
+pub const ATTEMPTS: usize = 5;
+
+// Reads made through the returned database are part of the transaction, so its commit
+// only succeeds if nothing it read has changed since.
+pub fn reads(firestore: &FirestoreDb, transaction: &FirestoreTransaction<'_>) -> FirestoreDb {
+  firestore.clone_with_consistency_selector(FirestoreConsistencySelector::Transaction(
+    transaction.transaction_id().clone(),
+  ))
+}
+
+// Returns false when a conflicting commit aborted the transaction and it should be
+// run again.
+pub async fn commit(transaction: FirestoreTransaction<'_>) -> Result<bool, Error> {
+  match transaction.commit().await {
+    Ok(_) => Ok(true),
+    Err(FirestoreError::DatabaseError(error)) if error.retry_possible => Ok(false),
+    Err(error) => Err(error.into()),
+  }
+}
+
+// Rolls back a transaction that won't be committed, releasing what it read.
+pub async fn abandon<T>(
+  transaction: FirestoreTransaction<'_>,
+  result: Result<T, Error>,
+) -> Result<T, Error> {
+  transaction.rollback().await?;
+  result
+}
+
+pub fn contended() -> Error {
+  Error::Other("Transaction kept conflicting with other writes.".into())
+}